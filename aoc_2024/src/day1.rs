@@ -1,6 +1,8 @@
 use camino::Utf8PathBuf;
-use clap::Subcommand;
-use std::collections::HashMap;
+use clap::{Args, Subcommand, ValueEnum};
+use std::collections::{HashMap, HashSet};
+use std::time::{Duration, Instant};
+use std::{error, fmt};
 
 use crate::read_file;
 
@@ -11,71 +13,237 @@ pub enum Day1Commands {
         /// Input File Path
         #[arg(short, long)]
         path: Utf8PathBuf,
+        #[command(flatten)]
+        columns: ColumnPair,
+        /// Algorithm used to sort the lists
+        #[arg(long, value_enum, default_value_t = SortAlgorithm::Std)]
+        sort_algorithm: SortAlgorithm,
     },
     /// Calculate the Similarity Score from two lists
     Score {
         /// Input File Path
         #[arg(short, long)]
         path: Utf8PathBuf,
+        #[command(flatten)]
+        columns: ColumnPair,
+        /// Algorithm used to sort the lists
+        #[arg(long, value_enum, default_value_t = SortAlgorithm::Std)]
+        sort_algorithm: SortAlgorithm,
+    },
+    /// Compare the two lists with distance, overlap and rank correlation metrics
+    Compare {
+        /// Input File Path
+        #[arg(short, long)]
+        path: Utf8PathBuf,
+        #[command(flatten)]
+        columns: ColumnPair,
+        /// Algorithm used to sort the lists
+        #[arg(long, value_enum, default_value_t = SortAlgorithm::Std)]
+        sort_algorithm: SortAlgorithm,
+    },
+    /// Time each sort algorithm against the same generated list
+    BenchmarkSort {
+        /// Number of values in the generated list
+        #[arg(long, default_value_t = 5000)]
+        size: usize,
+        /// Number of times each algorithm sorts the list, timings are averaged
+        #[arg(long, default_value_t = 5)]
+        runs: usize,
     },
 }
 
 pub fn day1_cli_command_processing(command: &Day1Commands) {
     match command {
-        Day1Commands::TotalDistance { path } => {
+        Day1Commands::TotalDistance {
+            path,
+            columns,
+            sort_algorithm,
+        } => {
             info!("Command received to calculate Total Distance");
-            println!("Total Distance: {}", calculate_distance(path.clone()));
+            match calculate_distance(path.clone(), *columns, *sort_algorithm) {
+                Ok(distance) => println!("Total Distance: {}", distance),
+                Err(error) => error!("{}", error),
+            }
         }
-        Day1Commands::Score { path } => {
+        Day1Commands::Score {
+            path,
+            columns,
+            sort_algorithm,
+        } => {
             info!("Command received to calculate Similarity Score");
-            println!("Total Similarity Score: {}", calculate_score(path.clone()));
+            match calculate_score(path.clone(), *columns, *sort_algorithm) {
+                Ok(score) => println!("Total Similarity Score: {}", score),
+                Err(error) => error!("{}", error),
+            }
+        }
+        Day1Commands::Compare {
+            path,
+            columns,
+            sort_algorithm,
+        } => {
+            info!("Command received to compare lists");
+            let comparison = match compare_list_file(path.clone(), *columns, *sort_algorithm) {
+                Ok(comparison) => comparison,
+                Err(error) => {
+                    error!("{}", error);
+                    return;
+                }
+            };
+            println!("Total Distance: {}", comparison.total_distance);
+            println!("Max Distance: {}", comparison.max_distance);
+            println!("Median Distance: {}", comparison.median_distance);
+            println!("Intersection Size: {}", comparison.intersection);
+            println!("Jaccard Similarity: {:.4}", comparison.jaccard);
+            match comparison.spearman {
+                Some(spearman) => println!("Spearman Rank Correlation: {:.4}", spearman),
+                None => println!("Spearman Rank Correlation: undefined"),
+            }
+        }
+        Day1Commands::BenchmarkSort { size, runs } => {
+            info!("Command received to benchmark sort algorithms");
+            match benchmark_sorts(*size, *runs) {
+                Ok(timings) => {
+                    for (algorithm, elapsed) in timings {
+                        println!("{:<8} {:>12.2?}", format!("{:?}", algorithm), elapsed);
+                    }
+                }
+                Err(error) => error!("{}", error),
+            }
         }
     }
 }
 
-fn parse_day1_file(file_path: Utf8PathBuf) -> (Vec<i32>, Vec<i32>) {
+// Which two whitespace separated columns of the file to compare, numbered from 1
+#[derive(Args, Debug, Clone, Copy, PartialEq)]
+pub struct ColumnPair {
+    /// Column used as the left list
+    #[arg(long, default_value_t = 1)]
+    left_column: usize,
+    /// Column used as the right list
+    #[arg(long, default_value_t = 2)]
+    right_column: usize,
+}
+
+#[cfg(test)]
+const FIRST_TWO_COLUMNS: ColumnPair = ColumnPair {
+    left_column: 1,
+    right_column: 2,
+};
+
+// Every row must have as many values as the first, blank lines are skipped
+fn parse_columns(content: &str) -> Result<Vec<Vec<i32>>, Day1Error> {
+    let mut columns: Vec<Vec<i32>> = vec![];
+    for (line_index, line) in content.lines().enumerate() {
+        let values: Vec<&str> = line.split_whitespace().collect();
+        if values.is_empty() {
+            continue;
+        }
+        if columns.is_empty() {
+            columns = vec![vec![]; values.len()];
+        }
+        if values.len() != columns.len() {
+            return Err(Day1Error::MismatchedRow {
+                line: line_index + 1,
+                expected: columns.len(),
+                found: values.len(),
+            });
+        }
+        for (column, value) in columns.iter_mut().zip(values) {
+            let num: i32 = value.parse().map_err(|_| Day1Error::InvalidNumber {
+                line: line_index + 1,
+                value: value.to_owned(),
+            })?;
+            column.push(num);
+        }
+    }
+    Ok(columns)
+}
+
+fn parse_day1_file(
+    file_path: Utf8PathBuf,
+    columns: ColumnPair,
+) -> Result<(Vec<i32>, Vec<i32>), Day1Error> {
     info!("Parsing File");
     let content = read_file(file_path);
 
-    let mut left_list: Vec<i32> = vec![];
-    let mut right_list: Vec<i32> = vec![];
-    let split_content = content.split("\n");
-    for line in split_content {
-        let split_line = line.split("   ");
-        for (index, value) in split_line.enumerate() {
-            let num: i32 = value.parse().expect("To be a valid number");
-            if index == 0 {
-                left_list.append(&mut vec![num]);
+    let parsed_columns = parse_columns(&content)?;
+    let column = |column: usize| -> Result<Vec<i32>, Day1Error> {
+        column
+            .checked_sub(1)
+            .and_then(|index| parsed_columns.get(index))
+            .cloned()
+            .ok_or(Day1Error::ColumnOutOfRange {
+                column,
+                columns: parsed_columns.len(),
+            })
+    };
+    let left_list = column(columns.left_column)?;
+    let right_list = column(columns.right_column)?;
+    debug!("Left List  '{:?}'", left_list);
+    debug!("Right List '{:?}'", right_list);
+
+    Ok((left_list, right_list))
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq)]
+pub enum SortAlgorithm {
+    /// Original bubble sort, quadratic, kept for comparison
+    Bubble,
+    /// Top down merge sort
+    Merge,
+    /// Least significant digit radix sort, a byte at a time
+    Radix,
+    /// The standard library's unstable sort
+    Std,
+}
+
+const SORT_ALGORITHMS: [SortAlgorithm; 4] = [
+    SortAlgorithm::Bubble,
+    SortAlgorithm::Merge,
+    SortAlgorithm::Radix,
+    SortAlgorithm::Std,
+];
+
+fn sort_list(short_first: bool, list: &mut Vec<i32>, algorithm: SortAlgorithm) -> Vec<i32> {
+    info!("Sorting List with {:?}...", algorithm);
+
+    match algorithm {
+        SortAlgorithm::Bubble => bubble_sort(short_first, list),
+        SortAlgorithm::Merge => {
+            *list = merge_sort(list);
+            if !short_first {
+                list.reverse();
+            }
+        }
+        SortAlgorithm::Radix => {
+            radix_sort(list);
+            if !short_first {
+                list.reverse();
+            }
+        }
+        SortAlgorithm::Std => {
+            if short_first {
+                list.sort_unstable();
             } else {
-                right_list.append(&mut vec![num]);
+                list.sort_unstable_by(|a, b| b.cmp(a));
             }
         }
     }
-    debug!("Left List  '{:?}'", left_list);
-    debug!("Right List '{:?}'", right_list);
 
-    (left_list, right_list)
+    debug!("Sorted List {:?}", list);
+    info!("Sorting Complete");
+    list.clone()
 }
 
-fn sort_list(short_first: bool, list: &mut Vec<i32>) -> Vec<i32> {
-    info!("Sorting List...");
-
+fn bubble_sort(short_first: bool, list: &mut [i32]) {
     // Suboptimal how with loop?
     loop {
         let mut swaps = 0;
-        debug!("Sorting...");
-        for index in 0..list.len() - 1 {
+        trace!("Sorting...");
+        for index in 0..list.len().saturating_sub(1) {
             let value = list[index];
             let next_value = list[index + 1];
 
-            // debug!(
-            //     "list[{}]:'{}', list[{}]:'{}'",
-            //     index,
-            //     value,
-            //     index + 1,
-            //     next_value
-            // );
-
             if (value > next_value) && short_first {
                 list.swap(index, index + 1);
                 swaps += 1;
@@ -85,39 +253,130 @@ fn sort_list(short_first: bool, list: &mut Vec<i32>) -> Vec<i32> {
                 list.swap(index, index + 1);
                 swaps += 1;
             }
-
-            debug!("List '{:?}'", list);
         }
+        trace!("List '{:?}'", list);
         if swaps == 0 {
-            debug!("Sorted List {:?}", list);
-            info!("Sorting Complete");
             break;
         }
     }
+}
 
-    list.clone()
+fn merge_sort(list: &[i32]) -> Vec<i32> {
+    if list.len() <= 1 {
+        return list.to_vec();
+    }
+    let (left, right) = list.split_at(list.len() / 2);
+    let left = merge_sort(left);
+    let right = merge_sort(right);
+
+    let mut merged = Vec::with_capacity(list.len());
+    let (mut left_index, mut right_index) = (0, 0);
+    while left_index < left.len() && right_index < right.len() {
+        if left[left_index] <= right[right_index] {
+            merged.push(left[left_index]);
+            left_index += 1;
+        } else {
+            merged.push(right[right_index]);
+            right_index += 1;
+        }
+    }
+    merged.extend_from_slice(&left[left_index..]);
+    merged.extend_from_slice(&right[right_index..]);
+    merged
 }
 
-fn tupilize(left_list: Vec<i32>, right_list: Vec<i32>) -> Vec<(i32, i32)> {
+// Flipping the sign bit maps i32 onto u32 in the same order, so negative values sort before positive ones
+fn radix_sort(list: &mut [i32]) {
+    let mut keys: Vec<u32> = list
+        .iter()
+        .map(|value| *value as u32 ^ 0x8000_0000)
+        .collect();
+    let mut buffer = vec![0; keys.len()];
+    for shift in (0..32).step_by(8) {
+        let mut counts = [0usize; 257];
+        for key in &keys {
+            counts[((key >> shift) & 0xff) as usize + 1] += 1;
+        }
+        for digit in 0..256 {
+            counts[digit + 1] += counts[digit];
+        }
+        for key in &keys {
+            let digit = ((key >> shift) & 0xff) as usize;
+            buffer[counts[digit]] = *key;
+            counts[digit] += 1;
+        }
+        std::mem::swap(&mut keys, &mut buffer);
+    }
+    for (value, key) in list.iter_mut().zip(keys) {
+        *value = (key ^ 0x8000_0000) as i32;
+    }
+}
+
+// Deterministic xorshift values, so every algorithm is timed against the same list without pulling in a rand crate
+fn generate_benchmark_list(size: usize) -> Vec<i32> {
+    let mut state: u64 = 0x2545_f491_4f6c_dd1d;
+    (0..size)
+        .map(|_| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            (state % 100_000) as i32
+        })
+        .collect()
+}
+
+// Average time each algorithm takes to sort the same list, failing on the first one that sorts it wrong
+fn benchmark_sorts(size: usize, runs: usize) -> Result<Vec<(SortAlgorithm, Duration)>, Day1Error> {
+    let list = generate_benchmark_list(size);
+    let mut expected = list.clone();
+    expected.sort_unstable();
+
+    let mut timings = vec![];
+    for algorithm in SORT_ALGORITHMS {
+        let mut elapsed = Duration::ZERO;
+        for _ in 0..runs.max(1) {
+            let mut unsorted = list.clone();
+            let start = Instant::now();
+            let sorted = sort_list(true, &mut unsorted, algorithm);
+            elapsed += start.elapsed();
+            if sorted != expected {
+                // A list that only differs in length first goes wrong where the shorter one ends
+                let index = expected
+                    .iter()
+                    .zip(&sorted)
+                    .position(|(expected, sorted)| expected != sorted)
+                    .unwrap_or(expected.len().min(sorted.len()));
+                return Err(Day1Error::IncorrectSort { algorithm, index });
+            }
+        }
+        timings.push((algorithm, elapsed / runs.max(1) as u32));
+    }
+    Ok(timings)
+}
+
+fn tupilize(left_list: Vec<i32>, right_list: Vec<i32>) -> Result<Vec<(i32, i32)>, Day1Error> {
     info!("Tupalizing Lists");
     debug!("Left List  '{:?}'", left_list);
     debug!("Right List '{:?}'", right_list);
-    let mut list: Vec<(i32, i32)> = vec![];
-    for index in 0..left_list.len() {
-        list.append(&mut vec![(left_list[index], right_list[index])]);
+    if left_list.len() != right_list.len() {
+        return Err(Day1Error::MismatchedLengths {
+            left: left_list.len(),
+            right: right_list.len(),
+        });
     }
+    let list: Vec<(i32, i32)> = left_list.into_iter().zip(right_list).collect();
     info!("Tupalized List");
     debug!("List: {:?}", list);
-    list
+    Ok(list)
 }
 
-fn count_similarities(list: &mut Vec<i32>) -> HashMap<i32, i32> {
+fn count_similarities(list: &mut Vec<i32>) -> HashMap<i32, u64> {
     info!("Counting similarities in List");
-    let mut count_map: HashMap<i32, i32> = HashMap::new();
+    let mut count_map: HashMap<i32, u64> = HashMap::new();
 
     for value in list {
         count_map
-            .entry(value.clone())
+            .entry(*value)
             .and_modify(|counter| *counter += 1)
             .or_insert(1);
     }
@@ -126,56 +385,294 @@ fn count_similarities(list: &mut Vec<i32>) -> HashMap<i32, i32> {
     count_map
 }
 
-fn calculate_distance(file_path: Utf8PathBuf) -> i32 {
+fn calculate_distance(
+    file_path: Utf8PathBuf,
+    columns: ColumnPair,
+    sort_algorithm: SortAlgorithm,
+) -> Result<u64, Day1Error> {
     info!("Beginning to calculate distance");
 
-    let (left_list, right_list) = parse_day1_file(file_path);
+    let (left_list, right_list) = parse_day1_file(file_path, columns)?;
+    total_distance(left_list, right_list, sort_algorithm)
+}
 
+// abs_diff keeps the distance between any two i32 values in a u32, so only the running total can overflow
+fn total_distance(
+    left_list: Vec<i32>,
+    right_list: Vec<i32>,
+    sort_algorithm: SortAlgorithm,
+) -> Result<u64, Day1Error> {
     debug!("Left List  '{:?}'", left_list);
     debug!("Right List '{:?}'", right_list);
 
     let tupalized_list = tupilize(
-        sort_list(true, &mut left_list.clone()),
-        sort_list(true, &mut right_list.clone()),
-    );
+        sort_list(true, &mut left_list.clone(), sort_algorithm),
+        sort_list(true, &mut right_list.clone(), sort_algorithm),
+    )?;
 
     info!("Calculating Distance");
-    let mut distance = 0;
+    let mut distance: u64 = 0;
     for tuple in tupalized_list {
-        distance += (tuple.0 - tuple.1).abs();
+        distance = distance
+            .checked_add(u64::from(tuple.0.abs_diff(tuple.1)))
+            .ok_or(Day1Error::TotalOverflow("distance"))?;
     }
 
     info!("Calculated Distance: '{}'", distance);
-    distance
+    Ok(distance)
 }
 
-fn calculate_score(file_path: Utf8PathBuf) -> i32 {
+fn calculate_score(
+    file_path: Utf8PathBuf,
+    columns: ColumnPair,
+    sort_algorithm: SortAlgorithm,
+) -> Result<i64, Day1Error> {
     info!("Beginning to calculate score");
 
-    let (left_list, right_list) = parse_day1_file(file_path);
+    let (left_list, right_list) = parse_day1_file(file_path, columns)?;
+    similarity_score(left_list, right_list, sort_algorithm)
+}
 
+fn similarity_score(
+    left_list: Vec<i32>,
+    right_list: Vec<i32>,
+    sort_algorithm: SortAlgorithm,
+) -> Result<i64, Day1Error> {
     debug!("Left List  '{:?}'", left_list);
     debug!("Right List '{:?}'", right_list);
 
-    let sorted_left_list = sort_list(true, &mut left_list.clone());
-    let count_map = count_similarities(&mut sort_list(true, &mut right_list.clone()));
+    let sorted_left_list = sort_list(true, &mut left_list.clone(), sort_algorithm);
+    let count_map = count_similarities(&mut sort_list(
+        true,
+        &mut right_list.clone(),
+        sort_algorithm,
+    ));
 
-    let mut score = 0;
+    let mut score: i64 = 0;
     for value in sorted_left_list {
-        score += value
-            * count_map
-                .get(&value)
-                .or(Some(&0))
-                .expect("To be a valid int");
+        let count = *count_map.get(&value).unwrap_or(&0);
+        score = i64::try_from(count)
+            .ok()
+            .and_then(|count| i64::from(value).checked_mul(count))
+            .and_then(|similarity| score.checked_add(similarity))
+            .ok_or(Day1Error::TotalOverflow("similarity score"))?;
     }
 
     info!("Calculated Score: {}", score);
-    score
+    Ok(score)
+}
+
+#[derive(Debug, PartialEq)]
+struct ListComparison {
+    total_distance: u64,
+    max_distance: u32,
+    median_distance: f64,
+    // Values the lists have in common, counting repeats up to the smaller count
+    intersection: u64,
+    // Intersection over union of the lists as multisets, 1 when both are empty
+    jaccard: f64,
+    // Rank correlation of the rows as given, None when there's nothing to correlate
+    spearman: Option<f64>,
+}
+
+// Ranks from each value's place in the sorted list, with tied values sharing the average of their ranks
+fn rank_list(list: &[i32], sort_algorithm: SortAlgorithm) -> Vec<f64> {
+    let sorted_list = sort_list(true, &mut list.to_vec(), sort_algorithm);
+    let mut ranks: HashMap<i32, f64> = HashMap::new();
+    let mut index = 0;
+    while index < sorted_list.len() {
+        let mut tied = index;
+        while tied + 1 < sorted_list.len() && sorted_list[tied + 1] == sorted_list[index] {
+            tied += 1;
+        }
+        // Ranks are 1 based, so the tied run covers ranks index + 1 through tied + 1
+        ranks.insert(sorted_list[index], (index + tied + 2) as f64 / 2.0);
+        index = tied + 1;
+    }
+    list.iter().map(|value| ranks[value]).collect()
+}
+
+// Spearman's rho as the Pearson correlation of the ranks, which stays correct with ties
+fn spearman_correlation(left_ranks: &[f64], right_ranks: &[f64]) -> Option<f64> {
+    if left_ranks.len() < 2 {
+        return None;
+    }
+    let mean = |ranks: &[f64]| ranks.iter().sum::<f64>() / ranks.len() as f64;
+    let (left_mean, right_mean) = (mean(left_ranks), mean(right_ranks));
+    let mut covariance = 0.0;
+    let mut left_variance = 0.0;
+    let mut right_variance = 0.0;
+    for (left_rank, right_rank) in left_ranks.iter().zip(right_ranks) {
+        covariance += (left_rank - left_mean) * (right_rank - right_mean);
+        left_variance += (left_rank - left_mean).powi(2);
+        right_variance += (right_rank - right_mean).powi(2);
+    }
+    if left_variance == 0.0 || right_variance == 0.0 {
+        return None;
+    }
+    Some(covariance / (left_variance * right_variance).sqrt())
+}
+
+fn compare_lists(
+    left_list: Vec<i32>,
+    right_list: Vec<i32>,
+    sort_algorithm: SortAlgorithm,
+) -> Result<ListComparison, Day1Error> {
+    info!("Comparing Lists");
+    let spearman = spearman_correlation(
+        &rank_list(&left_list, sort_algorithm),
+        &rank_list(&right_list, sort_algorithm),
+    );
+
+    let left_counts = count_similarities(&mut left_list.clone());
+    let right_counts = count_similarities(&mut right_list.clone());
+    let mut intersection = 0;
+    let mut union = 0;
+    let values: HashSet<&i32> = left_counts.keys().chain(right_counts.keys()).collect();
+    for value in values {
+        let left_count = *left_counts.get(value).unwrap_or(&0);
+        let right_count = *right_counts.get(value).unwrap_or(&0);
+        intersection += left_count.min(right_count);
+        union += left_count.max(right_count);
+    }
+    let jaccard = if union == 0 {
+        1.0
+    } else {
+        intersection as f64 / union as f64
+    };
+
+    let tupalized_list = tupilize(
+        sort_list(true, &mut left_list.clone(), sort_algorithm),
+        sort_list(true, &mut right_list.clone(), sort_algorithm),
+    )?;
+    let mut distances: Vec<u32> = tupalized_list
+        .iter()
+        .map(|tuple| tuple.0.abs_diff(tuple.1))
+        .collect();
+    distances.sort_unstable();
+    let median_distance = match distances.len() {
+        0 => 0.0,
+        length if length % 2 == 0 => {
+            (f64::from(distances[length / 2 - 1]) + f64::from(distances[length / 2])) / 2.0
+        }
+        length => f64::from(distances[length / 2]),
+    };
+    let total_distance = distances
+        .iter()
+        .try_fold(0u64, |total, distance| {
+            total.checked_add(u64::from(*distance))
+        })
+        .ok_or(Day1Error::TotalOverflow("distance"))?;
+
+    let comparison = ListComparison {
+        total_distance,
+        max_distance: distances.last().copied().unwrap_or(0),
+        median_distance,
+        intersection,
+        jaccard,
+        spearman,
+    };
+    debug!("Comparison: {:?}", comparison);
+    Ok(comparison)
+}
+
+fn compare_list_file(
+    file_path: Utf8PathBuf,
+    columns: ColumnPair,
+    sort_algorithm: SortAlgorithm,
+) -> Result<ListComparison, Day1Error> {
+    let (left_list, right_list) = parse_day1_file(file_path, columns)?;
+    compare_lists(left_list, right_list, sort_algorithm)
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Day1Error {
+    InvalidNumber {
+        line: usize,
+        value: String,
+    },
+    MismatchedRow {
+        line: usize,
+        expected: usize,
+        found: usize,
+    },
+    ColumnOutOfRange {
+        column: usize,
+        columns: usize,
+    },
+    MismatchedLengths {
+        left: usize,
+        right: usize,
+    },
+    TotalOverflow(&'static str),
+    IncorrectSort {
+        algorithm: SortAlgorithm,
+        index: usize,
+    },
+}
+
+impl fmt::Display for Day1Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Day1Error::InvalidNumber { line, value } => {
+                write!(f, "'{}' on line {} is not a valid number", value, line)
+            }
+            Day1Error::MismatchedRow {
+                line,
+                expected,
+                found,
+            } => {
+                write!(
+                    f,
+                    "line {} has {} values, expected {} like the first row",
+                    line, found, expected
+                )
+            }
+            Day1Error::ColumnOutOfRange { column, columns } => {
+                write!(
+                    f,
+                    "column {} doesn't exist, the file has {} columns numbered from 1",
+                    column, columns
+                )
+            }
+            Day1Error::MismatchedLengths { left, right } => {
+                write!(
+                    f,
+                    "lists have different lengths, {} on the left and {} on the right",
+                    left, right
+                )
+            }
+            Day1Error::TotalOverflow(total) => {
+                write!(f, "the {} overflowed a 64 bit total", total)
+            }
+            Day1Error::IncorrectSort { algorithm, index } => {
+                write!(
+                    f,
+                    "{:?} sorted the benchmark list incorrectly, first at index {}",
+                    algorithm, index
+                )
+            }
+        }
+    }
+}
+
+impl error::Error for Day1Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match *self {
+            Day1Error::InvalidNumber { .. } => None,
+            Day1Error::MismatchedRow { .. } => None,
+            Day1Error::ColumnOutOfRange { .. } => None,
+            Day1Error::MismatchedLengths { .. } => None,
+            Day1Error::TotalOverflow(_) => None,
+            Day1Error::IncorrectSort { .. } => None,
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use camino::Utf8PathBuf;
+    use proptest::prelude::*;
 
     use crate::test_init;
 
@@ -185,23 +682,123 @@ mod tests {
     fn test_read_file() {
         test_init();
         assert_eq!(
-            parse_day1_file(Utf8PathBuf::from("./src/puzzle_inputs/day1_sample.txt")),
-            (vec![3, 4, 2, 1, 3, 3], vec![4, 3, 5, 3, 9, 3])
+            parse_day1_file(
+                Utf8PathBuf::from("./src/puzzle_inputs/day1_sample.txt"),
+                FIRST_TWO_COLUMNS
+            ),
+            Ok((vec![3, 4, 2, 1, 3, 3], vec![4, 3, 5, 3, 9, 3]))
+        );
+    }
+
+    #[test]
+    fn parse_columns_with_any_whitespace() {
+        test_init();
+        assert_eq!(
+            Ok(vec![vec![3, 4, -2], vec![4, 3, 5]]),
+            parse_columns("3 4\n\n4\t3\r\n  -2     5  \n")
+        );
+        assert_eq!(Ok(vec![]), parse_columns("\n  \n"));
+    }
+
+    #[test]
+    fn parse_selected_columns() {
+        test_init();
+        assert_eq!(
+            Ok((vec![30, 31], vec![10, 11])),
+            parse_day1_file(
+                Utf8PathBuf::from("./src/puzzle_inputs/day1_sample_columns.txt"),
+                ColumnPair {
+                    left_column: 3,
+                    right_column: 1
+                }
+            )
+        );
+        assert_eq!(
+            Err(Day1Error::ColumnOutOfRange {
+                column: 4,
+                columns: 3
+            }),
+            parse_day1_file(
+                Utf8PathBuf::from("./src/puzzle_inputs/day1_sample_columns.txt"),
+                ColumnPair {
+                    left_column: 1,
+                    right_column: 4
+                }
+            )
+        );
+        assert_eq!(
+            Err(Day1Error::ColumnOutOfRange {
+                column: 0,
+                columns: 3
+            }),
+            parse_day1_file(
+                Utf8PathBuf::from("./src/puzzle_inputs/day1_sample_columns.txt"),
+                ColumnPair {
+                    left_column: 0,
+                    right_column: 2
+                }
+            )
+        );
+    }
+
+    #[test]
+    fn parse_malformed_rows() {
+        test_init();
+        assert_eq!(
+            Err(Day1Error::MismatchedRow {
+                line: 3,
+                expected: 2,
+                found: 1
+            }),
+            parse_columns("1 2\n3 4\n5\n")
+        );
+        assert_eq!(
+            Err(Day1Error::InvalidNumber {
+                line: 2,
+                value: "4x".to_owned()
+            }),
+            parse_columns("1 2\n3 4x\n")
         );
     }
 
     #[test]
     fn sort_array() {
         test_init();
-        let sorted_list = sort_list(true, &mut vec![3, 4, 2, 1, 3, 3]);
-        assert_eq!(sorted_list, vec![1, 2, 3, 3, 3, 4])
+        for algorithm in SORT_ALGORITHMS {
+            let sorted_list = sort_list(true, &mut vec![3, 4, 2, 1, 3, 3], algorithm);
+            assert_eq!(sorted_list, vec![1, 2, 3, 3, 3, 4], "{:?}", algorithm)
+        }
     }
 
     #[test]
     fn sort_array_reverse() {
         test_init();
-        let sorted_list = sort_list(false, &mut vec![3, 4, 2, 1, 3, 3]);
-        assert_eq!(sorted_list, vec![4, 3, 3, 3, 2, 1])
+        for algorithm in SORT_ALGORITHMS {
+            let sorted_list = sort_list(false, &mut vec![3, 4, 2, 1, 3, 3], algorithm);
+            assert_eq!(sorted_list, vec![4, 3, 3, 3, 2, 1], "{:?}", algorithm)
+        }
+    }
+
+    #[test]
+    fn sort_empty_and_single_value_arrays() {
+        test_init();
+        for algorithm in SORT_ALGORITHMS {
+            assert_eq!(sort_list(true, &mut vec![], algorithm), vec![] as Vec<i32>);
+            assert_eq!(sort_list(false, &mut vec![7], algorithm), vec![7]);
+        }
+    }
+
+    #[test]
+    fn benchmark_every_sort_algorithm() {
+        test_init();
+        let timings = benchmark_sorts(200, 1).unwrap();
+        assert_eq!(
+            SORT_ALGORITHMS.to_vec(),
+            timings
+                .iter()
+                .map(|(algorithm, _elapsed)| *algorithm)
+                .collect::<Vec<SortAlgorithm>>()
+        );
     }
 
     #[test]
@@ -210,7 +807,11 @@ mod tests {
         let tupalized_list = tupilize(vec![1, 2, 3, 3, 3, 4], vec![3, 3, 3, 4, 5, 9]);
         assert_eq!(
             tupalized_list,
-            vec![(1, 3), (2, 3), (3, 3), (3, 4), (3, 5), (4, 9)]
+            Ok(vec![(1, 3), (2, 3), (3, 3), (3, 4), (3, 5), (4, 9)])
+        );
+        assert_eq!(
+            Err(Day1Error::MismatchedLengths { left: 2, right: 1 }),
+            tupilize(vec![1, 2], vec![3])
         );
     }
 
@@ -229,15 +830,191 @@ mod tests {
     #[test]
     fn example_input() {
         test_init();
-        let total_distance =
-            calculate_distance(Utf8PathBuf::from("./src/puzzle_inputs/day1_sample.txt"));
-        assert_eq!(total_distance, 11);
+        let total_distance = calculate_distance(
+            Utf8PathBuf::from("./src/puzzle_inputs/day1_sample.txt"),
+            FIRST_TWO_COLUMNS,
+            SortAlgorithm::Std,
+        );
+        assert_eq!(total_distance, Ok(11));
+    }
+
+    #[test]
+    fn distance_of_extreme_values() {
+        test_init();
+        let size = 1 << 16;
+        assert_eq!(
+            Ok(u32::MAX as u64 * size as u64),
+            total_distance(
+                vec![i32::MIN; size],
+                vec![i32::MAX; size],
+                SortAlgorithm::Radix
+            )
+        );
+    }
+
+    #[test]
+    fn large_similarity_scores() {
+        test_init();
+        // Both lists share one value, so the score is value * size * size
+        let size = 1 << 16;
+        assert_eq!(
+            Ok(i32::MAX as i64 * (1 << 32)),
+            similarity_score(
+                vec![i32::MAX; size],
+                vec![i32::MAX; size],
+                SortAlgorithm::Std
+            )
+        );
+        assert_eq!(
+            Ok(i32::MIN as i64 * (1 << 32)),
+            similarity_score(
+                vec![i32::MIN; size],
+                vec![i32::MIN; size],
+                SortAlgorithm::Std
+            )
+        );
+        // One more row pushes i32::MAX * 65537 * 65537 past i64::MAX
+        assert_eq!(
+            Err(Day1Error::TotalOverflow("similarity score")),
+            similarity_score(
+                vec![i32::MAX; size + 1],
+                vec![i32::MAX; size + 1],
+                SortAlgorithm::Std
+            )
+        );
+        assert_eq!(
+            Err(Day1Error::TotalOverflow("similarity score")),
+            similarity_score(
+                vec![i32::MIN; size + 1],
+                vec![i32::MIN; size + 1],
+                SortAlgorithm::Std
+            )
+        );
     }
 
     #[test]
     fn example_similarity_score() {
         test_init();
-        let score = calculate_score(Utf8PathBuf::from("./src/puzzle_inputs/day1_sample.txt"));
-        assert_eq!(score, 31);
+        let score = calculate_score(
+            Utf8PathBuf::from("./src/puzzle_inputs/day1_sample.txt"),
+            FIRST_TWO_COLUMNS,
+            SortAlgorithm::Bubble,
+        );
+        assert_eq!(score, Ok(31));
+    }
+
+    #[test]
+    fn compare_sample_lists() {
+        test_init();
+        let comparison = compare_list_file(
+            Utf8PathBuf::from("./src/puzzle_inputs/day1_sample.txt"),
+            FIRST_TWO_COLUMNS,
+            SortAlgorithm::Std,
+        )
+        .unwrap();
+        assert_eq!(11, comparison.total_distance);
+        assert_eq!(5, comparison.max_distance);
+        assert_eq!(1.5, comparison.median_distance);
+        assert_eq!(4, comparison.intersection);
+        assert_eq!(0.5, comparison.jaccard);
+        assert!((comparison.spearman.unwrap() - -3.0 / 31.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn rank_list_averages_ties() {
+        test_init();
+        assert_eq!(
+            vec![4.0, 6.0, 2.0, 1.0, 4.0, 4.0],
+            rank_list(&[3, 4, 2, 1, 3, 3], SortAlgorithm::Merge)
+        );
+    }
+
+    #[test]
+    fn compare_degenerate_lists() {
+        test_init();
+        let empty = compare_lists(vec![], vec![], SortAlgorithm::Std).unwrap();
+        assert_eq!(
+            ListComparison {
+                total_distance: 0,
+                max_distance: 0,
+                median_distance: 0.0,
+                intersection: 0,
+                jaccard: 1.0,
+                spearman: None
+            },
+            empty
+        );
+        let constant = compare_lists(vec![1, 1, 1], vec![1, 2, 3], SortAlgorithm::Std).unwrap();
+        assert_eq!(None, constant.spearman);
+        assert_eq!(1, constant.intersection);
+        assert_eq!(1.0 / 5.0, constant.jaccard);
+        assert_eq!(
+            Some(-1.0),
+            compare_lists(vec![1, 2, 3], vec![30, 20, 10], SortAlgorithm::Std)
+                .unwrap()
+                .spearman
+        );
+    }
+
+    // Pairs the smallest remaining values of each list until both are empty
+    fn oracle_distance(mut left_list: Vec<i32>, mut right_list: Vec<i32>) -> u64 {
+        let mut distance = 0;
+        while let (Some(left), Some(right)) = (
+            left_list.iter().copied().min(),
+            right_list.iter().copied().min(),
+        ) {
+            distance += (left as i64 - right as i64).unsigned_abs();
+            left_list.remove(left_list.iter().position(|value| *value == left).unwrap());
+            right_list.remove(right_list.iter().position(|value| *value == right).unwrap());
+        }
+        distance
+    }
+
+    fn oracle_score(left_list: &[i32], right_list: &[i32]) -> i64 {
+        left_list
+            .iter()
+            .map(|left| {
+                *left as i64 * right_list.iter().filter(|right| *right == left).count() as i64
+            })
+            .sum()
+    }
+
+    fn paired_lists_strategy() -> impl Strategy<Value = (Vec<i32>, Vec<i32>)> {
+        (0usize..40).prop_flat_map(|length| {
+            let value = prop_oneof![-20..20i32, any::<i32>()];
+            (
+                proptest::collection::vec(value.clone(), length),
+                proptest::collection::vec(value, length),
+            )
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn prop_totals_match_oracle(
+            (left_list, right_list) in paired_lists_strategy(),
+            algorithm in proptest::sample::select(SORT_ALGORITHMS.to_vec())
+        ) {
+            prop_assert_eq!(
+                Ok(oracle_distance(left_list.clone(), right_list.clone())),
+                total_distance(left_list.clone(), right_list.clone(), algorithm)
+            );
+            prop_assert_eq!(
+                Ok(oracle_score(&left_list, &right_list)),
+                similarity_score(left_list, right_list, algorithm)
+            );
+        }
+
+        #[test]
+        fn prop_sort_algorithms_match_std(list in proptest::collection::vec(any::<i32>(), 0..200), short_first: bool) {
+            let mut expected = list.clone();
+            expected.sort_unstable();
+            if !short_first {
+                expected.reverse();
+            }
+            for algorithm in SORT_ALGORITHMS {
+                prop_assert_eq!(&expected, &sort_list(short_first, &mut list.clone(), algorithm));
+            }
+        }
     }
 }
@@ -1,5 +1,6 @@
 use camino::Utf8PathBuf;
-use clap::Subcommand;
+use clap::{Args, Subcommand, ValueEnum};
+use std::{error, fmt};
 
 use crate::read_file;
 
@@ -10,143 +11,512 @@ pub enum Day2Commands {
         /// Input File Path
         #[arg(short, long)]
         path: Utf8PathBuf,
-        /// Whether to use the dampener
-        #[arg(long, default_value_t = false)]
-        dampener: bool,
+        #[command(flatten)]
+        policy: SafetyArgs,
+    },
+    /// Gives each report's verdict, the first level that broke a rule and the levels removed to make it safe
+    Explain {
+        /// Input File Path
+        #[arg(short, long)]
+        path: Utf8PathBuf,
+        #[command(flatten)]
+        policy: SafetyArgs,
+        /// How to print the explanations
+        #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+        format: OutputFormat,
     },
 }
 
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq)]
+pub enum OutputFormat {
+    Text,
+    Csv,
+}
+
+// Rules a report has to follow to be safe, defaulting to the puzzle's
+#[derive(Args, Debug, Clone, Copy, PartialEq)]
+pub struct SafetyArgs {
+    /// Smallest allowed change between adjacent levels
+    #[arg(long, default_value_t = 1)]
+    min_step: u32,
+    /// Largest allowed change between adjacent levels
+    #[arg(long, default_value_t = 3)]
+    max_step: u32,
+    /// Which way the levels are allowed to move
+    #[arg(long, value_enum, default_value_t = DirectionPolicy::Either)]
+    direction: DirectionPolicy,
+    /// Whether to use the dampener, shorthand for --max-removals 1
+    #[arg(long, default_value_t = false, conflicts_with = "max_removals")]
+    dampener: bool,
+    /// Most levels that may be removed from a report to make it safe
+    #[arg(long, default_value_t = 0)]
+    max_removals: usize,
+}
+
+impl SafetyArgs {
+    fn policy(&self) -> Result<SafetyPolicy, Day2Error> {
+        let max_removals = if self.dampener { 1 } else { self.max_removals };
+        SafetyPolicy::new(self.min_step, self.max_step, self.direction, max_removals)
+    }
+}
+
 pub fn day2_cli_command_processing(command: &Day2Commands) {
     match command {
-        Day2Commands::Count { path, dampener } => {
+        Day2Commands::Count { path, policy } => {
             info!("Command received to count number of safe reports");
+            let policy = match policy.policy() {
+                Ok(policy) => policy,
+                Err(error) => {
+                    error!("{}", error);
+                    return;
+                }
+            };
             println!(
                 "Total Safe Reports: {}",
-                count_safe_reports(path.clone(), dampener.clone())
+                count_safe_reports(path.clone(), &policy)
             );
         }
+        Day2Commands::Explain {
+            path,
+            policy,
+            format,
+        } => {
+            info!("Command received to explain reports");
+            let policy = match policy.policy() {
+                Ok(policy) => policy,
+                Err(error) => {
+                    error!("{}", error);
+                    return;
+                }
+            };
+            let explanations = explain_reports(path.clone(), &policy);
+            match format {
+                OutputFormat::Text => {
+                    for explanation in explanations {
+                        println!("{}", explanation.to_text());
+                    }
+                }
+                OutputFormat::Csv => {
+                    println!("{}", EXPLANATION_CSV_HEADER);
+                    for explanation in explanations {
+                        println!("{}", explanation.to_csv_row());
+                    }
+                }
+            }
+        }
     }
 }
 
 type Report = Vec<Level>;
 type Level = i32;
 
+// Blank lines are skipped rather than read as empty reports
 fn parse_file(file_path: Utf8PathBuf) -> Vec<Report> {
     info!("Parsing File");
     let content = read_file(file_path);
-    let reports_string = content.split("\n");
 
     let mut reports: Vec<Report> = vec![];
-    for report_string in reports_string {
-        let levels_string = report_string.split(" ");
-        let levels: Vec<Level> = levels_string
-            .map(|level| level.parse::<i32>().expect("To be a valid number"))
+    for report_string in content.lines() {
+        let levels: Vec<Level> = report_string
+            .split_whitespace()
+            .map(|level| level.parse::<Level>().expect("To be a valid number"))
             .collect();
-        reports.append(&mut vec![levels]);
+        if !levels.is_empty() {
+            reports.push(levels);
+        }
     }
     reports
 }
 
-#[derive(PartialEq)]
+#[derive(PartialEq, Clone, Copy, Debug)]
 enum Direction {
     Decreasing,
     Increasing,
-    NotSet,
 }
 
-fn validate_increasing_or_decreasing(report: Report) -> bool {
-    info!("Validating Increasing or Decreasing Levels in Report");
-    debug!("Report {:?}", report);
-    let mut direction = Direction::NotSet;
-    let mut last_level = report[0];
-    for level in &report[1..] {
-        if direction == Direction::NotSet {
-            if &last_level < level {
-                direction = Direction::Increasing;
-                debug!("Report Determined to be increasing");
-            } else if &last_level > level {
-                direction = Direction::Decreasing;
-                debug!("Report Determined to be decreasing");
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq)]
+pub enum DirectionPolicy {
+    /// Every level is higher than the last
+    Increasing,
+    /// Every level is lower than the last
+    Decreasing,
+    /// Levels all increase or all decrease
+    Either,
+    /// Levels never go down, or never go up, so unchanged levels are allowed
+    NonStrict,
+}
+
+impl DirectionPolicy {
+    fn directions(&self) -> &'static [Direction] {
+        match self {
+            DirectionPolicy::Increasing => &[Direction::Increasing],
+            DirectionPolicy::Decreasing => &[Direction::Decreasing],
+            DirectionPolicy::Either | DirectionPolicy::NonStrict => {
+                &[Direction::Increasing, Direction::Decreasing]
             }
         }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct SafetyPolicy {
+    min_step: u32,
+    max_step: u32,
+    direction: DirectionPolicy,
+    max_removals: usize,
+}
+
+impl SafetyPolicy {
+    #[cfg(test)]
+    const STANDARD: SafetyPolicy = SafetyPolicy {
+        min_step: 1,
+        max_step: 3,
+        direction: DirectionPolicy::Either,
+        max_removals: 0,
+    };
 
-        if direction == Direction::Increasing && level < &last_level {
-            info!("Report failed validation - report not consistently increasing");
-            return false;
+    // Steps of 0 only make sense for the non-strict direction, which allows them regardless of min_step
+    fn new(
+        min_step: u32,
+        max_step: u32,
+        direction: DirectionPolicy,
+        max_removals: usize,
+    ) -> Result<SafetyPolicy, Day2Error> {
+        if min_step == 0 {
+            return Err(Day2Error::ZeroMinStep);
+        }
+        if min_step > max_step {
+            return Err(Day2Error::InvalidStepRange { min_step, max_step });
         }
-        if level == &last_level {
-            info!("Report failed validation - report level did not change between levels");
-            return false;
+        Ok(SafetyPolicy {
+            min_step,
+            max_step,
+            direction,
+            max_removals,
+        })
+    }
+
+    #[cfg(test)]
+    fn with_removals(self, max_removals: usize) -> SafetyPolicy {
+        SafetyPolicy {
+            max_removals,
+            ..self
         }
-        if direction == Direction::Decreasing && level > &last_level {
-            info!("Report failed validation - report not consistently decreasing");
-            return false;
+    }
+
+    // Steps are measured in the report's direction, widened so extreme levels can't overflow
+    fn valid_step(&self, direction: Direction, level: Level, next_level: Level) -> bool {
+        let step = match direction {
+            Direction::Increasing => i64::from(next_level) - i64::from(level),
+            Direction::Decreasing => i64::from(level) - i64::from(next_level),
+        };
+        (step == 0 && self.direction == DirectionPolicy::NonStrict)
+            || (i64::from(self.min_step)..=i64::from(self.max_step)).contains(&step)
+    }
+}
+
+// Fewest levels to remove so the rest of the report steps in the given direction, or None if that's more than max_removals.
+// removals[index] is the fewest removals from report[..=index] that keep the level at index, and since a gap wider than
+// max_removals can never be bridged only the previous max_removals + 1 levels need checking, so this is O(n * k)
+fn levels_to_remove(
+    report: &[Level],
+    direction: Direction,
+    policy: &SafetyPolicy,
+) -> Option<Vec<usize>> {
+    // Removing more levels than the report holds is never needed, and clamping keeps max_removals + 1 from overflowing
+    let max_removals = policy.max_removals.min(report.len());
+    if report.is_empty() {
+        return Some(vec![]);
+    }
+    let mut removals: Vec<usize> = Vec::with_capacity(report.len());
+    // The kept level before each kept level, so the removed ones can be recovered afterwards
+    let mut kept_before: Vec<Option<usize>> = Vec::with_capacity(report.len());
+    for (index, level) in report.iter().enumerate() {
+        // Keeping this level as the first one means removing everything before it
+        let mut fewest = index;
+        let mut before = None;
+        for previous in index.saturating_sub(max_removals + 1)..index {
+            let skipped = index - previous - 1;
+            if removals[previous] + skipped < fewest
+                && policy.valid_step(direction, report[previous], *level)
+            {
+                fewest = removals[previous] + skipped;
+                before = Some(previous);
+            }
         }
+        trace!("Level {} at {} needs {} removals", level, index, fewest);
+        removals.push(fewest);
+        kept_before.push(before);
+    }
+    // The last kept level decides how many trailing levels have to go
+    let (needed, last_kept) = removals
+        .iter()
+        .enumerate()
+        .skip(report.len().saturating_sub(max_removals + 1))
+        .map(|(index, fewest)| (fewest + report.len() - 1 - index, index))
+        .min()?;
+    debug!(
+        "Report {:?} needs {} removals {:?}",
+        report, needed, direction
+    );
+    if needed > max_removals {
+        return None;
+    }
 
-        last_level = level.clone();
+    let mut kept = vec![false; report.len()];
+    let mut index = Some(last_kept);
+    while let Some(kept_index) = index {
+        kept[kept_index] = true;
+        index = kept_before[kept_index];
     }
-    info!("Reported validated true");
-    true
+    Some(
+        kept.iter()
+            .enumerate()
+            .filter(|(_index, kept)| !**kept)
+            .map(|(index, _kept)| index)
+            .collect(),
+    )
 }
 
-fn validate_adjacency_difference(report: Report) -> bool {
-    info!("Validating Adjacency rules in report");
+// The fewest levels to remove in any direction the policy allows
+fn removal_plan(report: &[Level], policy: &SafetyPolicy) -> Option<Vec<usize>> {
+    policy
+        .direction
+        .directions()
+        .iter()
+        .filter_map(|direction| levels_to_remove(report, *direction, policy))
+        .min_by_key(|removed| removed.len())
+}
+
+// Rules only apply to pairs of adjacent levels, so empty and one level reports are always safe and a two level report
+// is decided by its one step. Removing levels can't make a report unsafe, so that's only tried when a pair fails
+fn validate_report(report: &[Level], policy: &SafetyPolicy) -> bool {
+    info!("Validating report with {:?}", policy);
     debug!("Report {:?}", report);
-    for (slice_index, level) in report[1..report.len() - 1].iter().enumerate() {
-        let index = slice_index + 1;
-        let backwards_difference = (level - report[index - 1]).abs();
-        let forwards_difference = (level - report[index + 1]).abs();
-
-        debug!(
-            "Levels - {} {} {}; backwards_difference: {}, forwards_difference: {}",
-            report[index - 1],
-            level,
-            report[index + 1],
-            backwards_difference,
-            forwards_difference
-        );
-        if backwards_difference > 3 {
-            info!("Report failed validation - levels exceeded difference");
-            return false;
+    let safe = match find_violation(report, policy) {
+        None => true,
+        Some(violation) => {
+            info!(
+                "Level at {} failed validation - {}",
+                violation.index, violation.rule
+            );
+            policy.max_removals > 0 && removal_plan(report, policy).is_some()
         }
-        if forwards_difference > 3 {
-            info!("Report failed validation - levels exceeded difference");
-            return false;
+    };
+    if safe {
+        info!("Report validated true");
+    } else {
+        info!("Report failed validation");
+    }
+    safe
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Rule {
+    Unchanged,
+    WrongDirection { expected: Direction },
+    StepTooSmall { step: i64, min_step: u32 },
+    StepTooLarge { step: i64, max_step: u32 },
+}
+
+impl Rule {
+    fn name(&self) -> &'static str {
+        match self {
+            Rule::Unchanged => "unchanged",
+            Rule::WrongDirection { .. } => "wrong-direction",
+            Rule::StepTooSmall { .. } => "step-too-small",
+            Rule::StepTooLarge { .. } => "step-too-large",
         }
     }
-    info!("Report validated true");
-    true
 }
 
-fn validate_report(report: Report, dampener: bool) -> bool {
-    if validate_increasing_or_decreasing(report.clone())
-        && validate_adjacency_difference(report.clone())
-    {
-        return true;
+impl fmt::Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Rule::Unchanged => write!(f, "level did not change"),
+            Rule::WrongDirection { expected } => {
+                write!(f, "level broke the report's {:?} direction", expected)
+            }
+            Rule::StepTooSmall { step, min_step } => {
+                write!(f, "step of {} is smaller than {}", step, min_step)
+            }
+            Rule::StepTooLarge { step, max_step } => {
+                write!(f, "step of {} is larger than {}", step, max_step)
+            }
+        }
     }
+}
 
-    if dampener == true {
-        info!("Dampener Set, attempting to remove levels to achieve successful report");
+// The first level that breaks the policy, compared against the level before it
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Violation {
+    index: usize,
+    rule: Rule,
+}
 
-        for (index, _level) in report.iter().enumerate() {
-            let mut altered_report = report.clone();
-            altered_report.remove(index);
-            if validate_increasing_or_decreasing(altered_report.clone())
-                && validate_adjacency_difference(altered_report)
-            {
-                return true;
+// A report's direction is fixed by the policy, or otherwise by its first change in level
+fn find_violation(report: &[Level], policy: &SafetyPolicy) -> Option<Violation> {
+    let direction = match policy.direction {
+        DirectionPolicy::Increasing => Direction::Increasing,
+        DirectionPolicy::Decreasing => Direction::Decreasing,
+        DirectionPolicy::Either | DirectionPolicy::NonStrict => report
+            .windows(2)
+            .find(|pair| pair[0] != pair[1])
+            .map_or(Direction::Increasing, |pair| {
+                if pair[0] < pair[1] {
+                    Direction::Increasing
+                } else {
+                    Direction::Decreasing
+                }
+            }),
+    };
+    for (index, pair) in report.windows(2).enumerate() {
+        let step = match direction {
+            Direction::Increasing => i64::from(pair[1]) - i64::from(pair[0]),
+            Direction::Decreasing => i64::from(pair[0]) - i64::from(pair[1]),
+        };
+        let rule = if step == 0 {
+            if policy.direction == DirectionPolicy::NonStrict {
+                continue;
+            }
+            Rule::Unchanged
+        } else if step < 0 {
+            Rule::WrongDirection {
+                expected: direction,
             }
+        } else if step < i64::from(policy.min_step) {
+            Rule::StepTooSmall {
+                step,
+                min_step: policy.min_step,
+            }
+        } else if step > i64::from(policy.max_step) {
+            Rule::StepTooLarge {
+                step,
+                max_step: policy.max_step,
+            }
+        } else {
+            continue;
+        };
+        return Some(Violation {
+            index: index + 1,
+            rule,
+        });
+    }
+    None
+}
+
+#[derive(Debug, PartialEq)]
+struct Explanation {
+    // 1 based position of the report in the file
+    report_number: usize,
+    report: Report,
+    violation: Option<Violation>,
+    // Levels whose removal made an otherwise unsafe report safe
+    removed: Option<Vec<usize>>,
+}
+
+const EXPLANATION_CSV_HEADER: &str =
+    "report,levels,verdict,failing_index,failing_level,rule,removed_indices,removed_levels";
+
+impl Explanation {
+    fn verdict(&self) -> &'static str {
+        match (&self.violation, &self.removed) {
+            (None, _) => "safe",
+            (Some(_), Some(_)) => "safe-with-removals",
+            (Some(_), None) => "unsafe",
         }
     }
-    return false;
+
+    fn removed_levels(&self) -> Vec<Level> {
+        self.removed
+            .iter()
+            .flatten()
+            .map(|index| self.report[*index])
+            .collect()
+    }
+
+    fn to_text(&self) -> String {
+        let mut text = format!(
+            "Report {} {:?}: {}",
+            self.report_number,
+            self.report,
+            self.verdict()
+        );
+        if let Some(violation) = &self.violation {
+            text += &format!(
+                ", index {} ({}) failed: {}",
+                violation.index, self.report[violation.index], violation.rule
+            );
+        }
+        if let Some(removed) = &self.removed {
+            text += &format!(
+                ", safe after removing indices {:?} (levels {:?})",
+                removed,
+                self.removed_levels()
+            );
+        }
+        text
+    }
+
+    fn to_csv_row(&self) -> String {
+        let join = |values: Vec<String>| values.join(" ");
+        let (failing_index, failing_level, rule) = match &self.violation {
+            Some(violation) => (
+                violation.index.to_string(),
+                self.report[violation.index].to_string(),
+                violation.rule.name().to_owned(),
+            ),
+            None => (String::new(), String::new(), String::new()),
+        };
+        [
+            self.report_number.to_string(),
+            join(self.report.iter().map(Level::to_string).collect()),
+            self.verdict().to_owned(),
+            failing_index,
+            failing_level,
+            rule,
+            join(
+                self.removed
+                    .iter()
+                    .flatten()
+                    .map(usize::to_string)
+                    .collect(),
+            ),
+            join(self.removed_levels().iter().map(Level::to_string).collect()),
+        ]
+        .join(",")
+    }
+}
+
+fn explain_report(report_number: usize, report: Report, policy: &SafetyPolicy) -> Explanation {
+    let violation = find_violation(&report, policy);
+    let removed = match violation {
+        Some(_) => removal_plan(&report, policy),
+        None => None,
+    };
+    Explanation {
+        report_number,
+        report,
+        violation,
+        removed,
+    }
+}
+
+fn explain_reports(file_path: Utf8PathBuf, policy: &SafetyPolicy) -> Vec<Explanation> {
+    info!("Explaining reports with {:?}", policy);
+    parse_file(file_path)
+        .into_iter()
+        .enumerate()
+        .map(|(index, report)| explain_report(index + 1, report, policy))
+        .collect()
 }
 
-fn count_safe_reports(file_path: Utf8PathBuf, dampener: bool) -> i32 {
+fn count_safe_reports(file_path: Utf8PathBuf, policy: &SafetyPolicy) -> i32 {
     let reports: Vec<Report> = parse_file(file_path);
     let mut count = 0;
 
     for report in reports {
-        if validate_report(report, dampener) {
+        if validate_report(&report, policy) {
             count += 1;
         }
     }
@@ -154,8 +524,45 @@ fn count_safe_reports(file_path: Utf8PathBuf, dampener: bool) -> i32 {
     count
 }
 
+#[derive(Debug, PartialEq)]
+pub enum Day2Error {
+    ZeroMinStep,
+    InvalidStepRange { min_step: u32, max_step: u32 },
+}
+
+impl fmt::Display for Day2Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Day2Error::ZeroMinStep => {
+                write!(
+                    f,
+                    "min step must be at least 1, use --direction non-strict to allow unchanged levels"
+                )
+            }
+            Day2Error::InvalidStepRange { min_step, max_step } => {
+                write!(
+                    f,
+                    "min step {} is larger than max step {}",
+                    min_step, max_step
+                )
+            }
+        }
+    }
+}
+
+impl error::Error for Day2Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match *self {
+            Day2Error::ZeroMinStep => None,
+            Day2Error::InvalidStepRange { .. } => None,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use proptest::prelude::*;
+
     use crate::test_init;
 
     use super::*;
@@ -179,51 +586,51 @@ mod tests {
     #[test]
     fn test_decreasing_report_success() {
         test_init();
-        assert!(validate_report(vec![7, 6, 4, 2, 1], false))
+        assert!(validate_report(&[7, 6, 4, 2, 1], &SafetyPolicy::STANDARD))
     }
 
     #[test]
     fn test_large_level_increase_failure() {
         test_init();
-        assert_eq!(false, validate_report(vec![1, 2, 7, 8, 9], false))
+        assert!(!validate_report(&[1, 2, 7, 8, 9], &SafetyPolicy::STANDARD))
     }
 
     #[test]
     fn test_large_level_decrease_failure() {
         test_init();
-        assert_eq!(false, validate_report(vec![9, 7, 6, 2, 1], false))
+        assert!(!validate_report(&[9, 7, 6, 2, 1], &SafetyPolicy::STANDARD))
     }
 
     #[test]
     fn test_direction_switch_failure() {
         test_init();
-        assert_eq!(false, validate_report(vec![1, 3, 2, 4, 5], false))
+        assert!(!validate_report(&[1, 3, 2, 4, 5], &SafetyPolicy::STANDARD))
     }
 
     #[test]
     fn test_levels_stable_failure() {
         test_init();
-        assert_eq!(false, validate_report(vec![8, 6, 4, 4, 1], false))
+        assert!(!validate_report(&[8, 6, 4, 4, 1], &SafetyPolicy::STANDARD))
     }
 
     #[test]
     fn test_increasing_report_success() {
         test_init();
-        assert!(validate_report(vec![1, 3, 6, 7, 9], false))
+        assert!(validate_report(&[1, 3, 6, 7, 9], &SafetyPolicy::STANDARD))
     }
 
     // Not mentioned in AoC, but I'm testing anyways
     #[test]
     fn test_decreasing_report_false() {
         test_init();
-        assert_eq!(false, validate_report(vec![7, 6, 4, 2, 3], false))
+        assert!(!validate_report(&[7, 6, 4, 2, 3], &SafetyPolicy::STANDARD))
     }
 
     // Not mentioned in AoC, but I'm testing anyways
     #[test]
     fn test_adjacency_levels_true() {
         test_init();
-        assert!(validate_report(vec![1, 3, 6, 7, 9], false))
+        assert!(validate_report(&[1, 3, 6, 7, 9], &SafetyPolicy::STANDARD))
     }
 
     #[test]
@@ -233,7 +640,7 @@ mod tests {
             2,
             count_safe_reports(
                 Utf8PathBuf::from("./src/puzzle_inputs/day2_sample.txt"),
-                false
+                &SafetyPolicy::STANDARD
             )
         )
     }
@@ -241,37 +648,55 @@ mod tests {
     #[test]
     fn test_dampener_decreasing_report_success() {
         test_init();
-        assert!(validate_report(vec![7, 6, 4, 2, 1], true))
+        assert!(validate_report(
+            &[7, 6, 4, 2, 1],
+            &SafetyPolicy::STANDARD.with_removals(1)
+        ))
     }
 
     #[test]
     fn test_dampener_large_level_increase_failure() {
         test_init();
-        assert_eq!(false, validate_report(vec![1, 2, 7, 8, 9], true))
+        assert!(!validate_report(
+            &[1, 2, 7, 8, 9],
+            &SafetyPolicy::STANDARD.with_removals(1)
+        ))
     }
 
     #[test]
     fn test_dampener_large_level_decrease_failure() {
         test_init();
-        assert_eq!(false, validate_report(vec![9, 7, 6, 2, 1], true))
+        assert!(!validate_report(
+            &[9, 7, 6, 2, 1],
+            &SafetyPolicy::STANDARD.with_removals(1)
+        ))
     }
 
     #[test]
     fn test_dampener_direction_switch_success() {
         test_init();
-        assert!(validate_report(vec![1, 3, 2, 4, 5], true))
+        assert!(validate_report(
+            &[1, 3, 2, 4, 5],
+            &SafetyPolicy::STANDARD.with_removals(1)
+        ))
     }
 
     #[test]
     fn test_dampener_levels_stable_success() {
         test_init();
-        assert!(validate_report(vec![8, 6, 4, 4, 1], true))
+        assert!(validate_report(
+            &[8, 6, 4, 4, 1],
+            &SafetyPolicy::STANDARD.with_removals(1)
+        ))
     }
 
     #[test]
     fn test_dampener_increasing_report_success() {
         test_init();
-        assert!(validate_report(vec![1, 3, 6, 7, 9], true))
+        assert!(validate_report(
+            &[1, 3, 6, 7, 9],
+            &SafetyPolicy::STANDARD.with_removals(1)
+        ))
     }
 
     #[test]
@@ -281,8 +706,363 @@ mod tests {
             4,
             count_safe_reports(
                 Utf8PathBuf::from("./src/puzzle_inputs/day2_sample.txt"),
-                true
+                &SafetyPolicy::STANDARD.with_removals(1)
             )
         )
     }
+
+    #[test]
+    fn test_multiple_removals() {
+        test_init();
+        // 10 and 11 both have to go
+        assert!(!validate_report(
+            &[1, 10, 11, 2, 3],
+            &SafetyPolicy::STANDARD.with_removals(1)
+        ));
+        assert!(validate_report(
+            &[1, 10, 11, 2, 3],
+            &SafetyPolicy::STANDARD.with_removals(2)
+        ));
+        assert_eq!(
+            Some(vec![1, 2]),
+            levels_to_remove(
+                &[1, 10, 11, 2, 3],
+                Direction::Increasing,
+                &SafetyPolicy::STANDARD.with_removals(5)
+            )
+        );
+        // Removing the first and last levels
+        assert_eq!(
+            Some(vec![0, 4]),
+            levels_to_remove(
+                &[9, 1, 2, 3, 0],
+                Direction::Increasing,
+                &SafetyPolicy::STANDARD.with_removals(2)
+            )
+        );
+        assert_eq!(
+            None,
+            levels_to_remove(
+                &[9, 1, 2, 3, 0],
+                Direction::Increasing,
+                &SafetyPolicy::STANDARD.with_removals(1)
+            )
+        );
+    }
+
+    #[test]
+    fn test_removals_on_long_report() {
+        test_init();
+        let mut report: Vec<Level> = (0..100_000).collect();
+        report[500] = 0;
+        report[70_000] = -5;
+        assert!(validate_report(
+            &report,
+            &SafetyPolicy::STANDARD.with_removals(2)
+        ));
+        assert!(!validate_report(
+            &report,
+            &SafetyPolicy::STANDARD.with_removals(1)
+        ));
+        assert_eq!(
+            Some(vec![500, 70_000]),
+            levels_to_remove(
+                &report,
+                Direction::Increasing,
+                &SafetyPolicy::STANDARD.with_removals(2)
+            )
+        );
+    }
+
+    #[test]
+    fn test_unbounded_removals() {
+        test_init();
+        let policy = SafetyPolicy::STANDARD.with_removals(usize::MAX);
+        assert!(validate_report(&[1, 2, 9, 4], &policy));
+        assert!(validate_report(&[9, 1, 9, 1, 9], &policy));
+        assert_eq!(
+            Some(vec![2]),
+            levels_to_remove(&[1, 2, 9, 4], Direction::Increasing, &policy)
+        );
+    }
+
+    #[test]
+    fn test_extreme_levels() {
+        test_init();
+        assert!(!validate_report(
+            &[Level::MIN, Level::MAX],
+            &SafetyPolicy::STANDARD
+        ));
+        assert!(validate_report(
+            &[Level::MAX - 2, Level::MAX],
+            &SafetyPolicy::STANDARD
+        ));
+    }
+
+    #[test]
+    fn test_policy_validation() {
+        test_init();
+        assert_eq!(
+            Err(Day2Error::ZeroMinStep),
+            SafetyPolicy::new(0, 3, DirectionPolicy::Either, 0)
+        );
+        assert_eq!(
+            Err(Day2Error::InvalidStepRange {
+                min_step: 4,
+                max_step: 3
+            }),
+            SafetyPolicy::new(4, 3, DirectionPolicy::Either, 0)
+        );
+        assert_eq!(
+            Ok(SafetyPolicy::STANDARD),
+            SafetyPolicy::new(1, 3, DirectionPolicy::Either, 0)
+        );
+    }
+
+    #[test]
+    fn test_direction_policies() {
+        test_init();
+        let increasing = SafetyPolicy::new(1, 3, DirectionPolicy::Increasing, 0).unwrap();
+        let decreasing = SafetyPolicy::new(1, 3, DirectionPolicy::Decreasing, 0).unwrap();
+        let non_strict = SafetyPolicy::new(1, 3, DirectionPolicy::NonStrict, 0).unwrap();
+        assert!(validate_report(&[1, 3, 6, 7, 9], &increasing));
+        assert!(!validate_report(&[7, 6, 4, 2, 1], &increasing));
+        assert!(validate_report(&[7, 6, 4, 2, 1], &decreasing));
+        assert!(!validate_report(&[1, 3, 6, 7, 9], &decreasing));
+        assert!(validate_report(&[8, 6, 4, 4, 1], &non_strict));
+        assert!(validate_report(&[1, 1, 1], &non_strict));
+        assert!(!validate_report(&[8, 6, 4, 4, 5], &non_strict));
+        assert!(!validate_report(&[1, 1, 5], &non_strict));
+    }
+
+    #[test]
+    fn test_step_bounds() {
+        test_init();
+        let wide = SafetyPolicy::new(2, 5, DirectionPolicy::Either, 0).unwrap();
+        assert!(validate_report(&[1, 3, 8, 10], &wide));
+        assert!(!validate_report(&[1, 3, 4, 6], &wide));
+        assert!(validate_report(&[1, 3, 4, 6], &wide.with_removals(1)));
+        assert_eq!(
+            4,
+            count_safe_reports(
+                Utf8PathBuf::from("./src/puzzle_inputs/day2_sample.txt"),
+                &SafetyPolicy::new(1, 5, DirectionPolicy::Either, 0).unwrap()
+            )
+        );
+    }
+
+    #[test]
+    fn test_find_violation() {
+        test_init();
+        let policy = SafetyPolicy::STANDARD;
+        assert_eq!(None, find_violation(&[7, 6, 4, 2, 1], &policy));
+        assert_eq!(
+            Some(Violation {
+                index: 2,
+                rule: Rule::StepTooLarge {
+                    step: 5,
+                    max_step: 3
+                }
+            }),
+            find_violation(&[1, 2, 7, 8, 9], &policy)
+        );
+        assert_eq!(
+            Some(Violation {
+                index: 2,
+                rule: Rule::WrongDirection {
+                    expected: Direction::Increasing
+                }
+            }),
+            find_violation(&[1, 3, 2, 4, 5], &policy)
+        );
+        assert_eq!(
+            Some(Violation {
+                index: 3,
+                rule: Rule::Unchanged
+            }),
+            find_violation(&[8, 6, 4, 4, 1], &policy)
+        );
+        assert_eq!(
+            Some(Violation {
+                index: 1,
+                rule: Rule::StepTooSmall {
+                    step: 1,
+                    min_step: 2
+                }
+            }),
+            find_violation(
+                &[1, 2, 4],
+                &SafetyPolicy::new(2, 3, DirectionPolicy::Either, 0).unwrap()
+            )
+        );
+        assert_eq!(
+            Some(Violation {
+                index: 1,
+                rule: Rule::WrongDirection {
+                    expected: Direction::Decreasing
+                }
+            }),
+            find_violation(
+                &[1, 3, 6],
+                &SafetyPolicy::new(1, 3, DirectionPolicy::Decreasing, 0).unwrap()
+            )
+        );
+    }
+
+    #[test]
+    fn test_explain_sample() {
+        test_init();
+        let explanations = explain_reports(
+            Utf8PathBuf::from("./src/puzzle_inputs/day2_sample.txt"),
+            &SafetyPolicy::STANDARD.with_removals(1),
+        );
+        assert_eq!(
+            vec![
+                "safe",
+                "unsafe",
+                "unsafe",
+                "safe-with-removals",
+                "safe-with-removals",
+                "safe"
+            ],
+            explanations
+                .iter()
+                .map(Explanation::verdict)
+                .collect::<Vec<&str>>()
+        );
+        // Removing either the 3 or the 2 works, ties keep the earlier level
+        assert_eq!(Some(vec![2]), explanations[3].removed);
+        assert_eq!(Some(vec![3]), explanations[4].removed);
+        assert_eq!(
+            "Report 4 [1, 3, 2, 4, 5]: safe-with-removals, index 2 (2) failed: level broke the report's Increasing direction, safe after removing indices [2] (levels [2])",
+            explanations[3].to_text()
+        );
+        assert_eq!("Report 1 [7, 6, 4, 2, 1]: safe", explanations[0].to_text());
+        assert_eq!(
+            "2,1 2 7 8 9,unsafe,2,7,step-too-large,,",
+            explanations[1].to_csv_row()
+        );
+        assert_eq!(
+            "5,8 6 4 4 1,safe-with-removals,3,4,unchanged,3,4",
+            explanations[4].to_csv_row()
+        );
+        assert_eq!("6,1 3 6 7 9,safe,,,,,", explanations[5].to_csv_row());
+    }
+
+    #[test]
+    fn test_short_reports() {
+        test_init();
+        let policy = SafetyPolicy::STANDARD;
+        assert!(validate_report(&[], &policy));
+        assert_eq!(None, find_violation(&[], &policy));
+        assert!(validate_report(&[5], &policy));
+        assert_eq!(None, find_violation(&[5], &policy));
+        assert!(validate_report(&[5, 8], &policy));
+        assert!(validate_report(&[8, 5], &policy));
+        assert!(!validate_report(&[5, 9], &policy));
+        assert!(!validate_report(&[5, 5], &policy));
+        assert_eq!(
+            Some(Violation {
+                index: 1,
+                rule: Rule::StepTooLarge {
+                    step: 4,
+                    max_step: 3
+                }
+            }),
+            find_violation(&[5, 9], &policy)
+        );
+        // Any two level report can drop down to one level
+        assert!(validate_report(&[5, 9], &policy.with_removals(1)));
+        assert_eq!(
+            Some(vec![1]),
+            removal_plan(&[5, 9], &policy.with_removals(1))
+        );
+        assert!(validate_report(&[5, 5], &policy.with_removals(1)));
+        assert!(validate_report(
+            &[5, 5],
+            &SafetyPolicy::new(1, 3, DirectionPolicy::NonStrict, 0).unwrap()
+        ));
+    }
+
+    // Checks the report against the policy's rules directly rather than through SafetyPolicy::valid_step
+    fn oracle_safe(report: &[Level], policy: &SafetyPolicy) -> bool {
+        let steps: Vec<i64> = report
+            .windows(2)
+            .map(|pair| i64::from(pair[1]) - i64::from(pair[0]))
+            .collect();
+        let in_range =
+            |step: i64| i64::from(policy.min_step) <= step && step <= i64::from(policy.max_step);
+        let increasing = steps.iter().all(|step| in_range(*step));
+        let decreasing = steps.iter().all(|step| in_range(-step));
+        match policy.direction {
+            DirectionPolicy::Increasing => increasing,
+            DirectionPolicy::Decreasing => decreasing,
+            DirectionPolicy::Either => increasing || decreasing,
+            DirectionPolicy::NonStrict => {
+                steps.iter().all(|step| *step == 0 || in_range(*step))
+                    || steps.iter().all(|step| *step == 0 || in_range(-step))
+            }
+        }
+    }
+
+    // Fewest removals that make the report safe, trying every subset of levels
+    fn oracle_fewest_removals(report: &[Level], policy: &SafetyPolicy) -> Option<usize> {
+        (0u32..1 << report.len())
+            .filter(|removed| removed.count_ones() as usize <= policy.max_removals)
+            .filter(|removed| {
+                let kept: Vec<Level> = report
+                    .iter()
+                    .enumerate()
+                    .filter(|(index, _level)| removed & (1 << index) == 0)
+                    .map(|(_index, level)| *level)
+                    .collect();
+                oracle_safe(&kept, policy)
+            })
+            .map(|removed| removed.count_ones() as usize)
+            .min()
+    }
+
+    fn policy_strategy() -> impl Strategy<Value = SafetyPolicy> {
+        (
+            1u32..4,
+            0u32..4,
+            prop_oneof![
+                Just(DirectionPolicy::Increasing),
+                Just(DirectionPolicy::Decreasing),
+                Just(DirectionPolicy::Either),
+                Just(DirectionPolicy::NonStrict)
+            ],
+            0usize..4,
+        )
+            .prop_map(|(min_step, extra_step, direction, max_removals)| {
+                SafetyPolicy::new(min_step, min_step + extra_step, direction, max_removals).unwrap()
+            })
+    }
+
+    proptest! {
+        #[test]
+        fn prop_validation_matches_oracle(
+            report in proptest::collection::vec(-8..8, 0..10),
+            policy in policy_strategy()
+        ) {
+            let fewest = oracle_fewest_removals(&report, &policy);
+            prop_assert_eq!(fewest.is_some(), validate_report(&report, &policy));
+            prop_assert_eq!(
+                oracle_safe(&report, &policy),
+                find_violation(&report, &policy).is_none()
+            );
+
+            let plan = removal_plan(&report, &policy);
+            prop_assert_eq!(fewest, plan.as_ref().map(Vec::len));
+            if let Some(removed) = plan {
+                let kept: Vec<Level> = report
+                    .iter()
+                    .enumerate()
+                    .filter(|(index, _level)| !removed.contains(index))
+                    .map(|(_index, level)| *level)
+                    .collect();
+                prop_assert!(oracle_safe(&kept, &policy));
+            }
+        }
+    }
 }
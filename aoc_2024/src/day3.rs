@@ -1,8 +1,9 @@
+use std::fmt;
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, Write};
+
 use camino::Utf8PathBuf;
 use clap::Subcommand;
-use regex::Regex;
-
-use crate::read_file;
 
 #[derive(Subcommand, Debug)]
 pub enum Day3Commands {
@@ -14,105 +15,578 @@ pub enum Day3Commands {
         /// Whether to use instructions found
         #[arg(long, default_value_t = false)]
         instructions: bool,
+        /// Whether to list every instruction as it's executed
+        #[arg(long, default_value_t = false)]
+        trace: bool,
+        /// Bytes of memory to read at a time
+        #[arg(long, default_value_t = 64 * 1024)]
+        chunk_size: usize,
+    },
+    /// Lists text that almost formed an instruction, and counts valid, disabled and malformed instructions
+    Analyze {
+        /// Input File Path
+        #[arg(short, long)]
+        path: Utf8PathBuf,
+        /// Whether to use instructions found
+        #[arg(long, default_value_t = false)]
+        instructions: bool,
     },
 }
 
 pub fn day3_cli_command_processing(command: &Day3Commands) {
     match command {
-        Day3Commands::MultiplyValidMemory { path, instructions } => {
+        Day3Commands::MultiplyValidMemory {
+            path,
+            instructions,
+            trace,
+            chunk_size,
+        } => {
             info!("Command received to multiply valid memory");
-            println!(
-                "Total from multiplications: {}",
-                multiply_valid_memory(path.clone(), instructions.clone())
-            );
+            let mut stdout = io::stdout();
+            let trace: Option<&mut dyn Write> = if *trace { Some(&mut stdout) } else { None };
+            match multiply_valid_memory(
+                path.clone(),
+                instructions.to_owned(),
+                chunk_size.to_owned(),
+                trace,
+            ) {
+                Ok(total) => println!("Total from multiplications: {}", total),
+                Err(error) => error!("Failed to read memory: {}", error),
+            }
+        }
+        Day3Commands::Analyze { path, instructions } => {
+            info!("Command received to analyze memory");
+            let analysis = match analyze_memory_file(path.clone(), instructions.to_owned()) {
+                Ok(analysis) => analysis,
+                Err(error) => {
+                    error!("Failed to read memory: {}", error);
+                    return;
+                }
+            };
+            println!("Near misses:");
+            for near_miss in &analysis.near_misses {
+                println!(
+                    "{:>8}  {:<16} {}",
+                    near_miss.offset,
+                    format!("{:?}", near_miss.text),
+                    near_miss.malformation
+                );
+            }
+            println!("Valid instructions: {}", analysis.valid);
+            println!("Disabled instructions: {}", analysis.disabled);
+            println!("Malformed instructions: {}", analysis.near_misses.len());
         }
     }
 }
 
-fn match_valid_memory(memory: String, check_instructions: bool) -> Vec<String> {
-    info!("Identifying valid memory");
-    let regex = Regex::new(r"(?<y>do\(\))|(?<n>don't\(\))|(?<m>mul\(\d{1,3},\d{1,3}\))")
-        .expect("To be a valid regex");
-    let mut valid_memory: Vec<String> = vec![];
-    let mut enabled = true;
-    let memory_captures = regex.captures_iter(&memory);
-    for memory in memory_captures {
-        if memory.name("y").is_some() && check_instructions {
-            debug!("Captured a `do()`");
-            enabled = true;
-        }
-        if memory.name("n").is_some() && check_instructions {
-            debug!("Captured a `don't()`");
-            enabled = false;
-        }
-        if memory.name("m").is_some() {
-            debug!("Captured a `mul(x,y)`");
-            let mul = memory
-                .name("m")
-                .expect("Expect capture to exist from regex")
-                .as_str()
-                .to_owned();
-            debug!("Mul: {}", mul);
-            if enabled {
-                debug!("Memory Enabled, adding mul to valid memory");
-                valid_memory.append(&mut vec![mul]);
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum Instruction {
+    Mul(u32, u32),
+    Do,
+    Dont,
+}
+
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Instruction::Mul(left, right) => write!(f, "mul({},{})", left, right),
+            Instruction::Do => write!(f, "do()"),
+            Instruction::Dont => write!(f, "don't()"),
+        }
+    }
+}
+
+// How an instruction is spelled in memory: `name(operand,operand,...)`, with 1-3 digit operands.
+// Adding an instruction means adding a variant, an entry here, and an arm in `Machine::execute`.
+struct InstructionSpec {
+    name: &'static str,
+    operands: usize,
+    build: fn(&[u32]) -> Instruction,
+}
+
+const INSTRUCTION_SET: [InstructionSpec; 3] = [
+    InstructionSpec {
+        name: "mul",
+        operands: 2,
+        build: |operands| Instruction::Mul(operands[0], operands[1]),
+    },
+    InstructionSpec {
+        name: "do",
+        operands: 0,
+        build: |_| Instruction::Do,
+    },
+    InstructionSpec {
+        name: "don't",
+        operands: 0,
+        build: |_| Instruction::Dont,
+    },
+];
+
+const MAX_OPERAND_DIGITS: usize = 3;
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+struct Token {
+    // Byte offset of the instruction's first character in memory
+    offset: usize,
+    instruction: Instruction,
+}
+
+// Why something that starts like an instruction isn't one. `found` is None at the end of memory.
+#[derive(Debug, PartialEq, Clone)]
+enum Malformation {
+    Expected { expected: char, found: Option<char> },
+    MissingOperand { found: Option<char> },
+    OperandTooLong { digits: usize },
+}
+
+impl fmt::Display for Malformation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let describe = |found: &Option<char>| match found {
+            Some(found) => format!("{:?}", found),
+            None => "end of memory".to_string(),
+        };
+        match self {
+            Malformation::Expected { expected, found } => {
+                write!(f, "expected {:?} but found {}", expected, describe(found))
+            }
+            Malformation::MissingOperand { found } => {
+                write!(f, "expected an operand but found {}", describe(found))
+            }
+            Malformation::OperandTooLong { digits } => {
+                write!(
+                    f,
+                    "operand has {} digits, at most {} are allowed",
+                    digits, MAX_OPERAND_DIGITS
+                )
+            }
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum ScanFailure {
+    // Memory doesn't start with the instruction's name, so it isn't worth reporting
+    NotAnInstruction,
+    // The name matched but the rest didn't, the offending character ending `length` bytes in
+    Malformed {
+        length: usize,
+        malformation: Malformation,
+    },
+}
+
+impl InstructionSpec {
+    // Longest text this instruction can be spelled with
+    fn max_length(&self) -> usize {
+        self.name.len()
+            + "()".len()
+            + self.operands * MAX_OPERAND_DIGITS
+            + self.operands.saturating_sub(1)
+    }
+
+    // The instruction and its length in bytes, if memory spells it out exactly starting at `offset`
+    fn scan(&self, memory: &[u8], offset: usize) -> Result<(Instruction, usize), ScanFailure> {
+        if !memory[offset..].starts_with(self.name.as_bytes()) {
+            return Err(ScanFailure::NotAnInstruction);
+        }
+        let mut position = offset + self.name.len();
+        let found = |position: usize| char_at(memory, position).map(|(char, _length)| char);
+        let through_found = |position: usize| {
+            position + char_at(memory, position).map_or(0, |(_char, length)| length)
+        };
+        let malformed = |end: usize, malformation: Malformation| ScanFailure::Malformed {
+            length: end - offset,
+            malformation,
+        };
+        let expect = |expected: char, position: &mut usize| -> Result<(), ScanFailure> {
+            if found(*position) == Some(expected) {
+                *position += expected.len_utf8();
+                Ok(())
             } else {
-                debug!("Memored disabled, not adding mul to valid memory");
+                Err(malformed(
+                    through_found(*position),
+                    Malformation::Expected {
+                        expected,
+                        found: found(*position),
+                    },
+                ))
+            }
+        };
+
+        expect('(', &mut position)?;
+        let mut operands = vec![];
+        for operand_index in 0..self.operands {
+            if operand_index > 0 {
+                expect(',', &mut position)?;
+            }
+            let digits = memory[position..]
+                .iter()
+                .take_while(|byte| byte.is_ascii_digit())
+                .count();
+            if digits == 0 {
+                return Err(malformed(
+                    through_found(position),
+                    Malformation::MissingOperand {
+                        found: found(position),
+                    },
+                ));
             }
+            if digits > MAX_OPERAND_DIGITS {
+                return Err(malformed(
+                    position + digits,
+                    Malformation::OperandTooLong { digits },
+                ));
+            }
+            let operand = memory[position..position + digits]
+                .iter()
+                .fold(0, |operand, digit| operand * 10 + (digit - b'0') as u32);
+            operands.push(operand);
+            position += digits;
         }
+        expect(')', &mut position)?;
+
+        Ok(((self.build)(&operands), position - offset))
     }
-    info!("Calculated Valid Memory");
-    debug!("Valid Memory: {:?}", valid_memory);
-    valid_memory
 }
 
-fn multiply_valid_memory(file_path: Utf8PathBuf, check_instructions: bool) -> i32 {
+// The character starting at `position` and its length in bytes. Memory is corrupted, so bytes that
+// aren't valid UTF-8 are read as a replacement character each rather than failing the whole scan.
+fn char_at(memory: &[u8], position: usize) -> Option<(char, usize)> {
+    let rest = memory.get(position..).filter(|rest| !rest.is_empty())?;
+    (1..=rest.len().min(4))
+        .find_map(|length| {
+            std::str::from_utf8(&rest[..length])
+                .ok()
+                .and_then(|text| text.chars().next())
+                .map(|char| (char, length))
+        })
+        .or(Some((char::REPLACEMENT_CHARACTER, 1)))
+}
+
+// Longest text any instruction can be spelled with, so a scan never needs to look further ahead than this
+fn max_instruction_length() -> usize {
+    INSTRUCTION_SET
+        .iter()
+        .map(|spec| spec.max_length())
+        .max()
+        .unwrap_or(0)
+}
+
+// Text that starts like an instruction but isn't one
+#[derive(Debug, PartialEq, Clone)]
+struct NearMiss {
+    offset: usize,
+    // From the start of the instruction name up to and including the offending character
+    text: String,
+    malformation: Malformation,
+}
+
+#[derive(Debug, PartialEq)]
+enum Scanned {
+    Instruction(Instruction, usize),
+    // An instruction name without a valid instruction, described by whichever instruction got furthest before failing
+    NearMiss(usize, Malformation),
+    Nothing,
+}
+
+fn scan_position(memory: &[u8], offset: usize) -> Scanned {
+    let mut furthest_malformation: Option<(usize, Malformation)> = None;
+    for spec in &INSTRUCTION_SET {
+        match spec.scan(memory, offset) {
+            Ok((instruction, length)) => return Scanned::Instruction(instruction, length),
+            Err(ScanFailure::Malformed {
+                length,
+                malformation,
+            }) => {
+                if furthest_malformation
+                    .as_ref()
+                    .is_none_or(|(furthest_length, _)| length > *furthest_length)
+                {
+                    furthest_malformation = Some((length, malformation));
+                }
+            }
+            Err(ScanFailure::NotAnInstruction) => {}
+        }
+    }
+    match furthest_malformation {
+        Some((length, malformation)) => Scanned::NearMiss(length, malformation),
+        None => Scanned::Nothing,
+    }
+}
+
+// Walks memory a byte at a time, skipping past each instruction found so matches never overlap.
+// Instruction names are ASCII, so stepping into the middle of a multi-byte character can't produce a match.
+fn scan_memory(memory: &[u8]) -> (Vec<Token>, Vec<NearMiss>) {
+    info!("Scanning memory for instructions");
+    let mut tokens = vec![];
+    let mut near_misses = vec![];
+    let mut offset = 0;
+    while offset < memory.len() {
+        match scan_position(memory, offset) {
+            Scanned::Instruction(instruction, length) => {
+                debug!("Scanned `{}` at {}", instruction, offset);
+                tokens.push(Token {
+                    offset,
+                    instruction,
+                });
+                offset += length;
+                continue;
+            }
+            Scanned::NearMiss(length, malformation) => {
+                debug!("Near miss at {}: {}", offset, malformation);
+                near_misses.push(NearMiss {
+                    offset,
+                    text: String::from_utf8_lossy(&memory[offset..offset + length]).into_owned(),
+                    malformation,
+                });
+            }
+            Scanned::Nothing => {}
+        }
+        offset += 1;
+    }
+    (tokens, near_misses)
+}
+
+#[cfg(test)]
+fn scan_instructions(memory: &str) -> Vec<Token> {
+    scan_memory(memory.as_bytes()).0
+}
+
+// Scans instructions out of a reader a chunk at a time, keeping only the unscanned tail of the last chunk around.
+// Scanning waits until an instruction's worth of bytes is buffered past the current position, so an instruction
+// split across chunks is only looked at once it's whole.
+struct StreamScanner<R: BufRead> {
+    reader: R,
+    chunk_size: usize,
+    lookahead: usize,
+    buffer: Vec<u8>,
+    // Offset in memory of the first byte in the buffer
+    buffer_offset: usize,
+    // Next byte in the buffer to scan from
+    position: usize,
+    exhausted: bool,
+}
+
+impl<R: BufRead> StreamScanner<R> {
+    fn new(reader: R, chunk_size: usize) -> Self {
+        StreamScanner {
+            reader,
+            chunk_size: chunk_size.max(1),
+            lookahead: max_instruction_length(),
+            buffer: vec![],
+            buffer_offset: 0,
+            position: 0,
+            exhausted: false,
+        }
+    }
+
+    fn read_chunk(&mut self) -> io::Result<()> {
+        self.buffer.drain(..self.position);
+        self.buffer_offset += self.position;
+        self.position = 0;
+
+        let available = self.reader.fill_buf()?;
+        let length = available.len().min(self.chunk_size);
+        self.buffer.extend_from_slice(&available[..length]);
+        self.reader.consume(length);
+        if length == 0 {
+            self.exhausted = true;
+        }
+        Ok(())
+    }
+}
+
+impl<R: BufRead> Iterator for StreamScanner<R> {
+    type Item = io::Result<Token>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            while !self.exhausted && self.buffer.len() - self.position < self.lookahead {
+                if let Err(error) = self.read_chunk() {
+                    return Some(Err(error));
+                }
+            }
+            if self.position >= self.buffer.len() {
+                return None;
+            }
+
+            let offset = self.buffer_offset + self.position;
+            match scan_position(&self.buffer, self.position) {
+                Scanned::Instruction(instruction, length) => {
+                    debug!("Scanned `{}` at {}", instruction, offset);
+                    self.position += length;
+                    return Some(Ok(Token {
+                        offset,
+                        instruction,
+                    }));
+                }
+                Scanned::NearMiss(..) | Scanned::Nothing => self.position += 1,
+            }
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct Machine {
+    // Whether do()/don't() are obeyed, otherwise every mul counts
+    check_instructions: bool,
+    enabled: bool,
+    total: u64,
+}
+
+// What an instruction did when executed
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum Effect {
+    Added { product: u64, total: u64 },
+    Skipped,
+    Ignored,
+    Enabled,
+    Disabled,
+}
+
+impl fmt::Display for Effect {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Effect::Added { product, total } => write!(f, "+{} = {}", product, total),
+            Effect::Skipped => write!(f, "skipped, memory disabled"),
+            Effect::Ignored => write!(f, "ignored"),
+            Effect::Enabled => write!(f, "memory enabled"),
+            Effect::Disabled => write!(f, "memory disabled"),
+        }
+    }
+}
+
+impl Machine {
+    fn new(check_instructions: bool) -> Self {
+        Machine {
+            check_instructions,
+            enabled: true,
+            total: 0,
+        }
+    }
+
+    fn execute(&mut self, instruction: &Instruction) -> Effect {
+        match instruction {
+            Instruction::Mul(left, right) => {
+                if self.enabled {
+                    let product = *left as u64 * *right as u64;
+                    self.total += product;
+                    Effect::Added {
+                        product,
+                        total: self.total,
+                    }
+                } else {
+                    Effect::Skipped
+                }
+            }
+            Instruction::Do | Instruction::Dont if !self.check_instructions => Effect::Ignored,
+            Instruction::Do => {
+                self.enabled = true;
+                Effect::Enabled
+            }
+            Instruction::Dont => {
+                self.enabled = false;
+                Effect::Disabled
+            }
+        }
+    }
+}
+
+// Runs every instruction through a fresh machine, writing one trace line per instruction when asked
+fn run_instructions(
+    tokens: impl Iterator<Item = io::Result<Token>>,
+    check_instructions: bool,
+    mut trace: Option<&mut dyn Write>,
+) -> io::Result<u64> {
+    let mut machine = Machine::new(check_instructions);
+    for token in tokens {
+        let token = token?;
+        let effect = machine.execute(&token.instruction);
+        debug!("{} at {}: {}", token.instruction, token.offset, effect);
+        if let Some(trace) = trace.as_mut() {
+            writeln!(
+                trace,
+                "{:>8}  {:<12} {}",
+                token.offset,
+                token.instruction.to_string(),
+                effect
+            )?;
+        }
+    }
+    Ok(machine.total)
+}
+
+// Streams the file through the scanner, so memory use doesn't grow with the size of the dump
+fn multiply_valid_memory(
+    file_path: Utf8PathBuf,
+    check_instructions: bool,
+    chunk_size: usize,
+    trace: Option<&mut dyn Write>,
+) -> io::Result<u64> {
     info!("Multiplying Valid Memory...");
-    let content = read_file(file_path);
-    debug!("Memory: {:?}", content);
+    let reader = BufReader::new(File::open(file_path)?);
+    let total = run_instructions(
+        StreamScanner::new(reader, chunk_size),
+        check_instructions,
+        trace,
+    )?;
 
-    let valid_memory = match_valid_memory(content, check_instructions);
-    let mut count = 0;
+    info!("Calculated Multiply Total: {}", total);
+    Ok(total)
+}
 
-    for memory in valid_memory {
-        let trimmed_memory =
-            memory.trim_matches(|c| c == 'm' || c == 'u' || c == 'l' || c == '(' || c == ')');
-        debug!("Trimmed Memory: {}", trimmed_memory);
-        let split_memory: Vec<i32> = trimmed_memory
-            .split(",")
-            .map(|level| level.parse::<i32>().expect("To be a valid number"))
-            .collect();
+#[derive(Debug, PartialEq)]
+struct MemoryAnalysis {
+    near_misses: Vec<NearMiss>,
+    // Well-formed instructions that ran, including ignored do()/don't()
+    valid: usize,
+    // Well-formed instructions skipped because memory was disabled
+    disabled: usize,
+}
 
-        count += split_memory[0] * split_memory[1];
+fn analyze_memory(memory: &[u8], check_instructions: bool) -> MemoryAnalysis {
+    let (tokens, near_misses) = scan_memory(memory);
+    let mut machine = Machine::new(check_instructions);
+    let mut valid = 0;
+    let mut disabled = 0;
+    for token in tokens {
+        match machine.execute(&token.instruction) {
+            Effect::Skipped => disabled += 1,
+            _ => valid += 1,
+        }
+    }
+    MemoryAnalysis {
+        near_misses,
+        valid,
+        disabled,
     }
+}
 
-    info!("Calculated Multiply Total: {}", count);
-    count
+// Reads raw bytes rather than a string, since corrupted memory isn't guaranteed to be valid UTF-8
+fn analyze_memory_file(
+    file_path: Utf8PathBuf,
+    check_instructions: bool,
+) -> io::Result<MemoryAnalysis> {
+    info!("Analyzing Memory...");
+    let memory = fs::read(file_path)?;
+    Ok(analyze_memory(&memory, check_instructions))
 }
+
 #[cfg(test)]
 mod tests {
+    use proptest::prelude::*;
+
     use super::*;
     use crate::test_init;
 
     #[test]
     fn test_corrupted_memory() {
         test_init();
-        assert_eq!(
-            vec![] as Vec<String>,
-            match_valid_memory(String::from("mul(4*"), false)
-        );
-        assert_eq!(
-            vec![] as Vec<String>,
-            match_valid_memory(String::from("mul(6,9!"), false)
-        );
-        assert_eq!(
-            vec![] as Vec<String>,
-            match_valid_memory(String::from("?(12,34)"), false)
-        );
-        assert_eq!(
-            vec![] as Vec<String>,
-            match_valid_memory(String::from("mul ( 2 , 4 )"), false)
-        );
+        assert_eq!(vec![] as Vec<Token>, scan_instructions("mul(4*"));
+        assert_eq!(vec![] as Vec<Token>, scan_instructions("mul(6,9!"));
+        assert_eq!(vec![] as Vec<Token>, scan_instructions("?(12,34)"));
+        assert_eq!(vec![] as Vec<Token>, scan_instructions("mul ( 2 , 4 )"));
+        assert_eq!(vec![] as Vec<Token>, scan_instructions("mul(1234,5)"));
+        assert_eq!(vec![] as Vec<Token>, scan_instructions("do ()don't(x)"));
     }
 
     #[test]
@@ -121,20 +595,46 @@ mod tests {
 
         assert_eq!(
             vec![
-                String::from("mul(2,4)"),
-                String::from("mul(5,5)"),
-                String::from("mul(11,8)"),
-                String::from("mul(8,5)")
-            ] as Vec<String>,
-            match_valid_memory(
-                String::from(
-                    "xmul(2,4)%&mul[3,7]!@^do_not_mul(5,5)+mul(32,64]then(mul(11,8)mul(8,5))"
-                ),
-                false
+                Token {
+                    offset: 1,
+                    instruction: Instruction::Mul(2, 4)
+                },
+                Token {
+                    offset: 29,
+                    instruction: Instruction::Mul(5, 5)
+                },
+                Token {
+                    offset: 53,
+                    instruction: Instruction::Mul(11, 8)
+                },
+                Token {
+                    offset: 62,
+                    instruction: Instruction::Mul(8, 5)
+                },
+            ],
+            scan_instructions(
+                "xmul(2,4)%&mul[3,7]!@^do_not_mul(5,5)+mul(32,64]then(mul(11,8)mul(8,5))"
             )
         );
     }
 
+    #[test]
+    fn test_scan_conditional_instructions() {
+        test_init();
+        assert_eq!(
+            vec![
+                Instruction::Dont,
+                Instruction::Mul(1, 2),
+                Instruction::Do,
+                Instruction::Mul(3, 4)
+            ],
+            scan_instructions("don't()mumul(1,2)do()mul(3,4)")
+                .into_iter()
+                .map(|token| token.instruction)
+                .collect::<Vec<Instruction>>()
+        );
+    }
+
     #[test]
     fn test_sample_multiply_valid_memory() {
         test_init();
@@ -142,8 +642,11 @@ mod tests {
             161,
             multiply_valid_memory(
                 Utf8PathBuf::from("./src/puzzle_inputs/day3_sample.txt"),
-                false
+                false,
+                64 * 1024,
+                None
             )
+            .unwrap()
         );
     }
 
@@ -154,8 +657,280 @@ mod tests {
             48,
             multiply_valid_memory(
                 Utf8PathBuf::from("./src/puzzle_inputs/day3_sample2.txt"),
-                true
+                true,
+                64 * 1024,
+                None
+            )
+            .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_sample2_trace() {
+        test_init();
+        let mut trace: Vec<u8> = vec![];
+        multiply_valid_memory(
+            Utf8PathBuf::from("./src/puzzle_inputs/day3_sample2.txt"),
+            true,
+            64 * 1024,
+            Some(&mut trace),
+        )
+        .unwrap();
+        assert_eq!(
+            vec![
+                "       1  mul(2,4)     +8 = 8",
+                "      20  don't()      memory disabled",
+                "      28  mul(5,5)     skipped, memory disabled",
+                "      48  mul(11,8)    skipped, memory disabled",
+                "      59  do()         memory enabled",
+                "      64  mul(8,5)     +40 = 48",
+            ],
+            String::from_utf8(trace)
+                .unwrap()
+                .lines()
+                .collect::<Vec<&str>>()
+        );
+    }
+
+    #[test]
+    fn test_corrupted_memory_near_misses() {
+        test_init();
+        let near_miss = |memory: &str| {
+            let near_misses = scan_memory(memory.as_bytes()).1;
+            assert_eq!(1, near_misses.len(), "{:?}", near_misses);
+            (
+                near_misses[0].text.clone(),
+                near_misses[0].malformation.clone(),
             )
+        };
+        assert_eq!(
+            (
+                "mul(4*".to_string(),
+                Malformation::Expected {
+                    expected: ',',
+                    found: Some('*')
+                }
+            ),
+            near_miss("mul(4*")
+        );
+        assert_eq!(
+            (
+                "mul(6,9!".to_string(),
+                Malformation::Expected {
+                    expected: ')',
+                    found: Some('!')
+                }
+            ),
+            near_miss("mul(6,9!")
+        );
+        assert_eq!(
+            (
+                "mul ".to_string(),
+                Malformation::Expected {
+                    expected: '(',
+                    found: Some(' ')
+                }
+            ),
+            near_miss("mul ( 2 , 4 )")
+        );
+        assert_eq!(
+            (
+                "mul(32,64]".to_string(),
+                Malformation::Expected {
+                    expected: ')',
+                    found: Some(']')
+                }
+            ),
+            near_miss("mul(32,64]")
+        );
+        assert_eq!(
+            (
+                "mul(1234".to_string(),
+                Malformation::OperandTooLong { digits: 4 }
+            ),
+            near_miss("mul(1234,5)")
         );
+        assert_eq!(
+            (
+                "mul(,".to_string(),
+                Malformation::MissingOperand { found: Some(',') }
+            ),
+            near_miss("mul(,5)")
+        );
+        assert_eq!(
+            (
+                "don't(".to_string(),
+                Malformation::Expected {
+                    expected: ')',
+                    found: None
+                }
+            ),
+            near_miss("don't(")
+        );
+        assert!(scan_memory(b"?(12,34)").1.is_empty());
+    }
+
+    #[test]
+    fn test_sample2_analysis() {
+        test_init();
+        let analysis = analyze_memory_file(
+            Utf8PathBuf::from("./src/puzzle_inputs/day3_sample2.txt"),
+            true,
+        )
+        .unwrap();
+        assert_eq!(
+            vec![(10, "mul[".to_string()), (37, "mul(32,64]".to_string())],
+            analysis
+                .near_misses
+                .iter()
+                .map(|near_miss| (near_miss.offset, near_miss.text.clone()))
+                .collect::<Vec<(usize, String)>>()
+        );
+        assert_eq!(4, analysis.valid);
+        assert_eq!(2, analysis.disabled);
+        assert_eq!(
+            "expected ')' but found ']'",
+            analysis.near_misses[1].malformation.to_string()
+        );
+    }
+
+    #[test]
+    fn test_analyze_memory_file_with_invalid_utf8() {
+        test_init();
+        let analysis = analyze_memory_file(
+            Utf8PathBuf::from("./src/puzzle_inputs/day3_invalid_utf8.txt"),
+            true,
+        )
+        .unwrap();
+        assert_eq!(1, analysis.valid);
+        assert_eq!(0, analysis.disabled);
+        assert_eq!(
+            vec![(11, "mul(\u{fffd}".to_string())],
+            analysis
+                .near_misses
+                .iter()
+                .map(|near_miss| (near_miss.offset, near_miss.text.clone()))
+                .collect::<Vec<(usize, String)>>()
+        );
+        assert!(
+            analyze_memory_file(Utf8PathBuf::from("./src/puzzle_inputs/missing.txt"), true)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_stream_scanner_across_chunk_boundaries() {
+        test_init();
+        let memory = "don't()mul(123,456)xdo()mul(1,2)mul(12,3";
+        for chunk_size in 1..=memory.len() {
+            assert_eq!(
+                scan_instructions(memory),
+                StreamScanner::new(memory.as_bytes(), chunk_size)
+                    .collect::<io::Result<Vec<Token>>>()
+                    .unwrap(),
+                "chunk size {}",
+                chunk_size
+            );
+        }
+    }
+
+    #[test]
+    fn test_stream_scanner_keeps_state_across_chunks() {
+        test_init();
+        let memory = "mul(1,1)don't()".to_string() + &"x".repeat(100) + "mul(2,2)do()mul(3,3)";
+        assert_eq!(
+            10,
+            run_instructions(StreamScanner::new(memory.as_bytes(), 7), true, None).unwrap()
+        );
+        assert_eq!(
+            14,
+            run_instructions(StreamScanner::new(memory.as_bytes(), 7), false, None).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_scan_memory_with_invalid_utf8() {
+        test_init();
+        let (tokens, near_misses) = scan_memory(b"\xffmul(2,3)\xe2\x82mul(\xff");
+        assert_eq!(
+            vec![Token {
+                offset: 1,
+                instruction: Instruction::Mul(2, 3)
+            }],
+            tokens
+        );
+        assert_eq!(
+            vec![NearMiss {
+                offset: 11,
+                text: "mul(\u{fffd}".to_string(),
+                malformation: Malformation::MissingOperand {
+                    found: Some(char::REPLACEMENT_CHARACTER)
+                }
+            }],
+            near_misses
+        );
+    }
+
+    // Matches `mul\((\d{1,3}),(\d{1,3})\)|do\(\)|don't\(\)` at every position by hand, like the original regex did
+    fn oracle_total(memory: &str, check_instructions: bool) -> u64 {
+        let operand = |text: &str| -> Option<(u64, usize)> {
+            let digits = text.bytes().take_while(u8::is_ascii_digit).count();
+            (1..=3)
+                .contains(&digits)
+                .then(|| (text[..digits].parse().expect("digits"), digits))
+        };
+        let mut enabled = true;
+        let mut total = 0;
+        for offset in 0..memory.len() {
+            let rest = &memory.as_bytes()[offset..];
+            if rest.starts_with(b"do()") {
+                enabled = true;
+            } else if rest.starts_with(b"don't()") {
+                enabled = false;
+            } else if let Some(operands) = rest.strip_prefix(b"mul(") {
+                let operands = String::from_utf8_lossy(operands);
+                let Some((left, left_digits)) = operand(&operands) else {
+                    continue;
+                };
+                let Some(after_comma) = operands[left_digits..].strip_prefix(',') else {
+                    continue;
+                };
+                let Some((right, right_digits)) = operand(after_comma) else {
+                    continue;
+                };
+                if after_comma[right_digits..].starts_with(')') && (enabled || !check_instructions)
+                {
+                    total += left * right;
+                }
+            }
+        }
+        total
+    }
+
+    proptest! {
+        #[test]
+        fn prop_instructions_match_oracle(
+            memory in "(mul|do|don't|[(),0-9x]){0,60}",
+            check_instructions: bool
+        ) {
+            let (tokens, _near_misses) = scan_memory(memory.as_bytes());
+            prop_assert_eq!(
+                oracle_total(&memory, check_instructions),
+                run_instructions(tokens.into_iter().map(Ok), check_instructions, None).unwrap()
+            );
+        }
+
+        #[test]
+        fn prop_stream_scanner_matches_whole_memory_scan(
+            memory in "(mul|do|don't|[(),0-9x]){0,60}",
+            chunk_size in 1..16usize
+        ) {
+            prop_assert_eq!(
+                scan_instructions(&memory),
+                StreamScanner::new(memory.as_bytes(), chunk_size)
+                    .collect::<io::Result<Vec<Token>>>()
+                    .unwrap()
+            );
+        }
     }
 }
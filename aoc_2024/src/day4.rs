@@ -1,232 +1,654 @@
+use std::str::FromStr;
+use std::{error, fmt};
+
 use camino::Utf8PathBuf;
-use clap::Subcommand;
+use clap::{Subcommand, ValueEnum};
+use unicode_segmentation::UnicodeSegmentation;
 
 use crate::read_file;
 
 #[derive(Subcommand, Debug)]
 pub enum Day4Commands {
-    /// Searches Puzzle for XMAS/SAMX
+    /// Searches Puzzle for a word, XMAS/SAMX by default
     SearchPuzzle {
         /// Input File Path
         #[arg(short, long)]
         path: Utf8PathBuf,
+        /// Word to search for
+        #[arg(long, default_value = "XMAS")]
+        word: String,
+        /// Directions the word may be read in
+        #[arg(long, value_enum, default_value_t = DirectionSet::All)]
+        directions: DirectionSet,
+        /// Whether to print the puzzle with every letter outside a match replaced by '.'
+        #[arg(long, default_value_t = false)]
+        highlight: bool,
+        /// Whether to color each highlighted match with ANSI escape codes
+        #[arg(long, default_value_t = false, requires = "highlight")]
+        color: bool,
     },
     /// Searches Puzzle for X-MAS SAMMAS X
     SearchXMas {
         /// Input File Path
         #[arg(short, long)]
         path: Utf8PathBuf,
+        /// Whether to print the puzzle with every letter outside a match replaced by '.'
+        #[arg(long, default_value_t = false)]
+        highlight: bool,
+        /// Whether to color each highlighted match with ANSI escape codes
+        #[arg(long, default_value_t = false, requires = "highlight")]
+        color: bool,
+    },
+    /// Searches Puzzle for letter templates, e.g. "M.S/.A./M.S" with '.' matching any letter
+    SearchTemplate {
+        /// Input File Path
+        #[arg(short, long)]
+        path: Utf8PathBuf,
+        /// Template to search for, rows separated by '/'. Can be given multiple times
+        #[arg(short, long, required_unless_present = "template_file")]
+        template: Vec<Template>,
+        /// File with one template per line
+        #[arg(long)]
+        template_file: Option<Utf8PathBuf>,
+        /// Whether to also search for the templates rotated by quarter turns
+        #[arg(long, default_value_t = false)]
+        rotations: bool,
+        /// Whether to also search for the templates mirrored
+        #[arg(long, default_value_t = false)]
+        reflections: bool,
+        /// Whether to print the puzzle with every letter outside a match replaced by '.'
+        #[arg(long, default_value_t = false)]
+        highlight: bool,
+        /// Whether to color each highlighted match with ANSI escape codes
+        #[arg(long, default_value_t = false, requires = "highlight")]
+        color: bool,
     },
 }
 
 pub fn day4_cli_command_processing(command: &Day4Commands) {
     match command {
-        Day4Commands::SearchPuzzle { path } => {
+        Day4Commands::SearchPuzzle {
+            path,
+            word,
+            directions,
+            highlight,
+            color,
+        } => {
             info!("Command received to search puzzle");
-            println!("Total XMAS/SAMX: {}", search_puzzle(path.clone()));
+            match search_puzzle(path.clone(), word, directions.to_owned()) {
+                Ok((puzzle, matches)) => print_matches(word, &puzzle, &matches, *highlight, *color),
+                Err(error) => error!("{}", error),
+            }
         }
-        Day4Commands::SearchXMas { path } => {
+        Day4Commands::SearchXMas {
+            path,
+            highlight,
+            color,
+        } => {
             info!("Command received to search puzzle for X-MAS");
-            println!("Total X-MAS: {}", search_puzzle_for_x_mas(path.clone()));
+            match search_puzzle_for_x_mas(path.clone()) {
+                Ok((puzzle, matches)) => {
+                    print_matches("X-MAS", &puzzle, &matches, *highlight, *color)
+                }
+                Err(error) => error!("{}", error),
+            }
+        }
+        Day4Commands::SearchTemplate {
+            path,
+            template,
+            template_file,
+            rotations,
+            reflections,
+            highlight,
+            color,
+        } => {
+            info!("Command received to search puzzle for templates");
+            let search = load_templates(template, template_file.clone()).and_then(|templates| {
+                search_puzzle_for_templates(
+                    path.clone(),
+                    &templates,
+                    rotations.to_owned(),
+                    reflections.to_owned(),
+                )
+            });
+            match search {
+                Ok((puzzle, matches)) => {
+                    print_matches("template matches", &puzzle, &matches, *highlight, *color)
+                }
+                Err(error) => error!("{}", error),
+            }
         }
     }
 }
+
+fn print_matches<M: PuzzleMatch>(
+    label: &str,
+    puzzle: &Puzzle,
+    matches: &[M],
+    highlight: bool,
+    color: bool,
+) {
+    if highlight {
+        println!("{}", highlight_matches(puzzle, matches, color));
+    }
+    println!("Total {}: {}", label, matches.len());
+}
+
 type Puzzle = Vec<Line>;
-type Line = Vec<char>;
+type Line = Vec<Cell>;
+// One user-perceived character, so letters built from several code points (e.g. with combining accents) stay whole
+type Cell = String;
+
+fn graphemes(text: &str) -> Vec<Cell> {
+    text.graphemes(true)
+        .map(|grapheme| grapheme.to_owned())
+        .collect()
+}
 
-fn parse_file(file_path: Utf8PathBuf) -> Puzzle {
+fn parse_file(file_path: Utf8PathBuf) -> Result<Puzzle, Day4Error> {
+    parse_puzzle(&read_file(file_path))
+}
+
+// Searches assume a rectangular grid, so the first line that doesn't match the width of the first is rejected
+fn parse_puzzle(contents: &str) -> Result<Puzzle, Day4Error> {
     let mut puzzle: Puzzle = vec![];
 
-    let contents = read_file(file_path);
-    let lines = contents.split("\n");
-    for line in lines {
-        let vec_line: Line = line.chars().into_iter().collect();
-        puzzle.append(&mut vec![vec_line]);
+    // Editors usually save a newline after the last row, which isn't an empty row of the puzzle
+    for (line_index, line) in contents.trim_end_matches(['\r', '\n']).lines().enumerate() {
+        let vec_line: Line = graphemes(line);
+        if let Some(first_line) = puzzle.first() {
+            if vec_line.len() != first_line.len() {
+                return Err(Day4Error::RaggedLine {
+                    line: line_index + 1,
+                    expected: first_line.len(),
+                    found: vec_line.len(),
+                });
+            }
+        }
+        puzzle.push(vec_line);
     }
 
-    puzzle
+    Ok(puzzle)
+}
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+enum Direction {
+    North,
+    NorthEast,
+    East,
+    SouthEast,
+    South,
+    SouthWest,
+    West,
+    NorthWest,
+}
+
+impl Direction {
+    // (x, y) step, with y growing downwards like the puzzle lines
+    fn delta(&self) -> (isize, isize) {
+        match self {
+            Direction::North => (0, -1),
+            Direction::NorthEast => (1, -1),
+            Direction::East => (1, 0),
+            Direction::SouthEast => (1, 1),
+            Direction::South => (0, 1),
+            Direction::SouthWest => (-1, 1),
+            Direction::West => (-1, 0),
+            Direction::NorthWest => (-1, -1),
+        }
+    }
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq)]
+pub enum DirectionSet {
+    /// Horizontal, vertical and diagonal, both forwards and backwards
+    All,
+    /// Horizontal and vertical only
+    Orthogonal,
+    /// Diagonal only
+    Diagonal,
 }
 
-fn search_iterator_for_xmas(line: Line) -> i32 {
-    let mut count = 0;
-    for (index, char) in line.iter().enumerate().clone() {
-        if char == &'X'
-            && index + 3 < line.len()
-            && line[index + 1] == 'M'
-            && line[index + 2] == 'A'
-            && line[index + 3] == 'S'
-        {
-            count += 1
+impl DirectionSet {
+    fn directions(&self) -> Vec<Direction> {
+        match self {
+            DirectionSet::All => vec![
+                Direction::North,
+                Direction::NorthEast,
+                Direction::East,
+                Direction::SouthEast,
+                Direction::South,
+                Direction::SouthWest,
+                Direction::West,
+                Direction::NorthWest,
+            ],
+            DirectionSet::Orthogonal => vec![
+                Direction::North,
+                Direction::East,
+                Direction::South,
+                Direction::West,
+            ],
+            DirectionSet::Diagonal => vec![
+                Direction::NorthEast,
+                Direction::SouthEast,
+                Direction::SouthWest,
+                Direction::NorthWest,
+            ],
         }
     }
-    debug!("Line '{:?}', count of XMAS '{}'", line, count);
-    count
 }
 
-fn search_iterator_for_samx(line: Line) -> i32 {
-    let mut count = 0;
-    for (index, char) in line.iter().enumerate().clone() {
-        if char == &'S'
-            && index + 3 < line.len()
-            && line[index + 1] == 'A'
-            && line[index + 2] == 'M'
-            && line[index + 3] == 'X'
-        {
-            count += 1
+#[derive(Debug, PartialEq, Clone, Copy)]
+struct WordMatch {
+    x: usize,
+    y: usize,
+    direction: Direction,
+    length: usize,
+}
+
+struct WordSearch {
+    word: Vec<Cell>,
+    directions: Vec<Direction>,
+}
+
+impl WordSearch {
+    fn new(word: &str, directions: &[Direction]) -> Self {
+        WordSearch {
+            word: graphemes(word),
+            directions: directions.to_vec(),
         }
     }
-    debug!("Line '{:?}', count of SAMX '{}'", line, count);
-    count
+
+    // Every start cell and direction that spells out the word. Palindromes show up once per direction they read in.
+    fn search(&self, puzzle: &Puzzle) -> Vec<WordMatch> {
+        let mut matches = vec![];
+        if self.word.is_empty() {
+            return matches;
+        }
+
+        for (y, line) in puzzle.iter().enumerate() {
+            for (x, cell) in line.iter().enumerate() {
+                if cell != &self.word[0] {
+                    continue;
+                }
+                for direction in &self.directions {
+                    if self.matches_from(puzzle, x, y, direction) {
+                        debug!(
+                            "Found '{:?}' at ({},{}) heading {:?}",
+                            self.word, x, y, direction
+                        );
+                        matches.push(WordMatch {
+                            x,
+                            y,
+                            direction: *direction,
+                            length: self.word.len(),
+                        });
+                    }
+                }
+            }
+        }
+
+        matches
+    }
+
+    fn matches_from(&self, puzzle: &Puzzle, x: usize, y: usize, direction: &Direction) -> bool {
+        let (delta_x, delta_y) = direction.delta();
+        self.word.iter().enumerate().all(|(offset, letter)| {
+            let cell_x = x.checked_add_signed(delta_x * offset as isize);
+            let cell_y = y.checked_add_signed(delta_y * offset as isize);
+            match (cell_x, cell_y) {
+                (Some(cell_x), Some(cell_y)) => puzzle
+                    .get(cell_y)
+                    .and_then(|line| line.get(cell_x))
+                    .is_some_and(|cell| cell == letter),
+                _ => false,
+            }
+        })
+    }
 }
 
-fn search_horizontally(puzzle: Puzzle) -> i32 {
-    let mut count = 0;
+fn search_puzzle(
+    file_path: Utf8PathBuf,
+    word: &str,
+    directions: DirectionSet,
+) -> Result<(Puzzle, Vec<WordMatch>), Day4Error> {
+    let puzzle: Puzzle = parse_file(file_path)?;
+    let matches = WordSearch::new(word, &directions.directions()).search(&puzzle);
+    Ok((puzzle, matches))
+}
 
-    for line in puzzle {
-        count += search_iterator_for_xmas(line.clone());
-        count += search_iterator_for_samx(line);
+// A rectangular shape of letters to find in the puzzle, where None cells match anything.
+// Written as rows separated by '/', with '.' as the wildcard, e.g. "M.S/.A./M.S".
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+pub struct Template {
+    cells: Vec<Vec<Option<Cell>>>,
+}
+
+impl FromStr for Template {
+    type Err = Day4Error;
+
+    fn from_str(template: &str) -> Result<Self, Self::Err> {
+        let cells: Vec<Vec<Option<Cell>>> = template
+            .trim()
+            .split('/')
+            .map(|row| {
+                graphemes(row)
+                    .into_iter()
+                    .map(|cell| if cell == "." { None } else { Some(cell) })
+                    .collect()
+            })
+            .collect();
+        if cells[0].is_empty() || cells.iter().any(|row| row.len() != cells[0].len()) {
+            return Err(Day4Error::InvalidTemplate(template.to_owned()));
+        }
+        Ok(Template { cells })
     }
+}
 
-    count
+impl fmt::Display for Template {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let rows: Vec<String> = self
+            .cells
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .map(|cell| cell.as_deref().unwrap_or("."))
+                    .collect()
+            })
+            .collect();
+        write!(f, "{}", rows.join("/"))
+    }
 }
 
-fn search_vertically(puzzle: Puzzle) -> i32 {
-    let mut count = 0;
-    let mut index = 0;
-    while index < puzzle[0].len() {
-        let mut vertical_line: Vec<char> = vec![];
-        for line in &puzzle {
-            vertical_line.push(line[index]);
+impl Template {
+    fn width(&self) -> usize {
+        self.cells[0].len()
+    }
+
+    fn height(&self) -> usize {
+        self.cells.len()
+    }
+
+    // Quarter turn clockwise
+    fn rotate(&self) -> Template {
+        Template {
+            cells: (0..self.width())
+                .map(|x| {
+                    (0..self.height())
+                        .rev()
+                        .map(|y| self.cells[y][x].clone())
+                        .collect()
+                })
+                .collect(),
+        }
+    }
+
+    // Mirrored left to right
+    fn reflect(&self) -> Template {
+        Template {
+            cells: self
+                .cells
+                .iter()
+                .map(|row| row.iter().rev().cloned().collect())
+                .collect(),
         }
-        debug!("Vertical_Line: {:?}", vertical_line);
-        count += search_iterator_for_xmas(vertical_line.clone());
-        count += search_iterator_for_samx(vertical_line);
-        index += 1;
-    }
-
-    count
-}
-
-// This is somewhat inefficient and I don't like, but I don't care to fix it right now. Bite me.
-fn search_diagonally_right(puzzle: Puzzle) -> i32 {
-    let mut count = 0;
-
-    for (line_index, line) in puzzle.iter().enumerate() {
-        for (char_index, _char) in line.iter().enumerate() {
-            let mut x_index = char_index;
-            let mut y_index = line_index;
-            let mut diagonal_line: Vec<char> = vec![];
-            while x_index < line.len()
-                && y_index < puzzle.len()
-                && x_index - char_index < 4
-                && y_index - line_index < 4
-            {
-                // debug!("x_index {}, y_index {}", x_index, y_index);
-                diagonal_line.push(puzzle[y_index][x_index]);
-                x_index += 1;
-                y_index += 1;
+    }
+
+    // The template plus any rotated/reflected variants, with symmetric duplicates removed so a spot isn't counted twice
+    fn orientations(&self, rotations: bool, reflections: bool) -> Vec<Template> {
+        let mut orientations = vec![self.clone()];
+        if reflections {
+            orientations.push(self.reflect());
+        }
+        if rotations {
+            for index in 0..orientations.len() {
+                let mut rotated = orientations[index].rotate();
+                for _ in 0..3 {
+                    orientations.push(rotated.clone());
+                    rotated = rotated.rotate();
+                }
+            }
+        }
+
+        let mut unique_orientations: Vec<Template> = vec![];
+        for orientation in orientations {
+            if !unique_orientations.contains(&orientation) {
+                unique_orientations.push(orientation);
             }
-            debug!("Diagonal Line: {:?}", diagonal_line);
-            count += search_iterator_for_xmas(diagonal_line.clone());
-            count += search_iterator_for_samx(diagonal_line);
         }
+        unique_orientations
     }
 
-    count
+    // Whether the template fits with its top left corner on (x, y)
+    fn matches_at(&self, puzzle: &Puzzle, x: usize, y: usize) -> bool {
+        self.cells.iter().enumerate().all(|(row_index, row)| {
+            row.iter().enumerate().all(|(cell_index, cell)| {
+                let puzzle_cell = puzzle
+                    .get(y + row_index)
+                    .and_then(|line| line.get(x + cell_index));
+                match (cell, puzzle_cell) {
+                    (_, None) => false,
+                    (None, Some(_)) => true,
+                    (Some(letter), Some(puzzle_letter)) => letter == puzzle_letter,
+                }
+            })
+        })
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+struct TemplateMatch {
+    x: usize,
+    y: usize,
+    template: Template,
 }
 
-fn search_diagonally_left(puzzle: Puzzle) -> i32 {
-    let mut count = 0;
+// Predefined template for the M-A-S cross, which covers all four X-MAS layouts once rotated
+const X_MAS_TEMPLATE: &str = "M.S/.A./M.S";
 
-    for (line_index, line) in puzzle.iter().enumerate() {
-        for (char_index, _char) in line.iter().enumerate() {
-            let mut x_index = char_index;
-            let mut y_index = line_index;
-            let mut diagonal_line: Vec<char> = vec![];
-            'line: while y_index < puzzle.len()
-                && char_index - x_index < 4
-                && y_index - line_index < 4
-            {
-                // debug!("x_index {}, y_index {}", x_index, y_index);
-                diagonal_line.push(puzzle[y_index][x_index]);
-                if x_index == 0 {
-                    break 'line;
+fn x_mas_templates() -> Vec<Template> {
+    X_MAS_TEMPLATE
+        .parse::<Template>()
+        .expect("To be a valid template")
+        .orientations(true, false)
+}
+
+// Templates given on the command line plus one per non-empty line of the template file
+fn load_templates(
+    templates: &[Template],
+    template_file: Option<Utf8PathBuf>,
+) -> Result<Vec<Template>, Day4Error> {
+    let mut loaded_templates = templates.to_vec();
+    if let Some(template_file) = template_file {
+        for line in read_file(template_file).lines() {
+            if !line.trim().is_empty() {
+                loaded_templates.push(line.parse()?);
+            }
+        }
+    }
+    Ok(loaded_templates)
+}
+
+// Tests every template at every position of the puzzle
+fn search_templates(puzzle: &Puzzle, templates: &[Template]) -> Vec<TemplateMatch> {
+    let mut matches = vec![];
+    for (y, line) in puzzle.iter().enumerate() {
+        for x in 0..line.len() {
+            for template in templates {
+                if template.matches_at(puzzle, x, y) {
+                    debug!("Template {} matched at ({},{})", template, x, y);
+                    matches.push(TemplateMatch {
+                        x,
+                        y,
+                        template: template.clone(),
+                    });
                 }
-                x_index -= 1;
-                y_index += 1;
             }
-            debug!("Diagonal Line: {:?}", diagonal_line);
-            count += search_iterator_for_xmas(diagonal_line.clone());
-            count += search_iterator_for_samx(diagonal_line);
         }
     }
+    matches
+}
 
-    count
+fn search_for_mas(puzzle: &Puzzle) -> Vec<TemplateMatch> {
+    search_templates(puzzle, &x_mas_templates())
 }
 
-fn search_diagonally(puzzle: Puzzle) -> i32 {
-    let mut count = 0;
-    count += search_diagonally_right(puzzle.clone());
-    count += search_diagonally_left(puzzle);
+fn search_puzzle_for_x_mas(
+    file_path: Utf8PathBuf,
+) -> Result<(Puzzle, Vec<TemplateMatch>), Day4Error> {
+    let puzzle: Puzzle = parse_file(file_path)?;
+    let matches = search_for_mas(&puzzle);
+    Ok((puzzle, matches))
+}
 
-    count
+fn search_puzzle_for_templates(
+    file_path: Utf8PathBuf,
+    templates: &[Template],
+    rotations: bool,
+    reflections: bool,
+) -> Result<(Puzzle, Vec<TemplateMatch>), Day4Error> {
+    let puzzle: Puzzle = parse_file(file_path)?;
+    let mut oriented_templates: Vec<Template> = vec![];
+    for template in templates {
+        for orientation in template.orientations(rotations, reflections) {
+            if !oriented_templates.contains(&orientation) {
+                oriented_templates.push(orientation);
+            }
+        }
+    }
+    let matches = search_templates(&puzzle, &oriented_templates);
+    Ok((puzzle, matches))
 }
 
-fn search_puzzle(file_path: Utf8PathBuf) -> i32 {
-    let mut count = 0;
-    let puzzle: Puzzle = parse_file(file_path);
-    count += search_diagonally(puzzle.clone());
-    count += search_horizontally(puzzle.clone());
-    count += search_vertically(puzzle);
-    count
+// Anything found in the puzzle that can say which cells it's made of
+trait PuzzleMatch {
+    fn cells(&self) -> Vec<(usize, usize)>;
 }
 
-// I anticipate this function to be a nightmare, yay
-// Future me: you were actually wrong lol
-fn search_for_mas(puzzle: Puzzle) -> i32 {
-    let mut count = 0;
+impl PuzzleMatch for WordMatch {
+    fn cells(&self) -> Vec<(usize, usize)> {
+        let (delta_x, delta_y) = self.direction.delta();
+        (0..self.length as isize)
+            .map(|offset| {
+                (
+                    self.x.wrapping_add_signed(delta_x * offset),
+                    self.y.wrapping_add_signed(delta_y * offset),
+                )
+            })
+            .collect()
+    }
+}
 
-    for (puzzle_index, line) in puzzle.iter().enumerate() {
-        for (line_index, char) in line.iter().enumerate() {
-            if char == &'A' {
-                if line_index as i32 - 1 >= 0
-                    && puzzle_index as i32 - 1 >= 0
-                    && line_index + 1 < line.len()
-                    && puzzle_index + 1 < puzzle.len()
-                {
-                    let top_left = puzzle[puzzle_index - 1][line_index - 1];
-                    let top_right = puzzle[puzzle_index - 1][line_index + 1];
-                    let bottom_left = puzzle[puzzle_index + 1][line_index - 1];
-                    let bottom_right = puzzle[puzzle_index + 1][line_index + 1];
-                    if (top_left == 'M' && bottom_right == 'S'
-                        || top_left == 'S' && bottom_right == 'M')
-                        && (top_right == 'M' && bottom_left == 'S'
-                            || top_right == 'S' && bottom_left == 'M')
-                    {
-                        info!("Valid X-MAS SAMMAS X");
-                        count += 1;
-                    }
+impl PuzzleMatch for TemplateMatch {
+    // Wildcards aren't part of what was matched, so only the letter cells count
+    fn cells(&self) -> Vec<(usize, usize)> {
+        let mut cells = vec![];
+        for (row_index, row) in self.template.cells.iter().enumerate() {
+            for (cell_index, cell) in row.iter().enumerate() {
+                if cell.is_some() {
+                    cells.push((self.x + cell_index, self.y + row_index));
                 }
             }
         }
+        cells
     }
-    count
 }
 
-fn search_puzzle_for_x_mas(file_path: Utf8PathBuf) -> i32 {
-    let mut count = 0;
-    let puzzle: Puzzle = parse_file(file_path);
-    count += search_for_mas(puzzle);
-    count
+// Red, green, yellow, blue, magenta, cyan
+const MATCH_COLORS: [u8; 6] = [31, 32, 33, 34, 35, 36];
+
+// The puzzle with every letter that isn't part of a match replaced by '.', like the puzzle description.
+// With color, each match gets its own color, and letters shared between matches are drawn in bold.
+fn highlight_matches<M: PuzzleMatch>(puzzle: &Puzzle, matches: &[M], color: bool) -> String {
+    let mut owners: Vec<Vec<Vec<usize>>> =
+        puzzle.iter().map(|line| vec![vec![]; line.len()]).collect();
+    for (match_index, puzzle_match) in matches.iter().enumerate() {
+        for (x, y) in puzzle_match.cells() {
+            owners[y][x].push(match_index);
+        }
+    }
+
+    puzzle
+        .iter()
+        .zip(owners)
+        .map(|(line, line_owners)| {
+            line.iter()
+                .zip(line_owners)
+                .map(|(letter, cell_owners)| match (&cell_owners[..], color) {
+                    ([], _) => ".".to_string(),
+                    (_, false) => letter.to_string(),
+                    ([match_index], true) => format!(
+                        "\x1b[{}m{}\x1b[0m",
+                        MATCH_COLORS[*match_index % MATCH_COLORS.len()],
+                        letter
+                    ),
+                    (_, true) => format!("\x1b[1m{}\x1b[0m", letter),
+                })
+                .collect::<String>()
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Day4Error {
+    InvalidTemplate(String),
+    RaggedLine {
+        line: usize,
+        expected: usize,
+        found: usize,
+    },
+}
+
+impl fmt::Display for Day4Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Day4Error::InvalidTemplate(template) => {
+                write!(
+                    f,
+                    "'{}' is not a valid template, expected equal length rows separated by '/', e.g. M.S/.A./M.S",
+                    template
+                )
+            }
+            Day4Error::RaggedLine {
+                line,
+                expected,
+                found,
+            } => {
+                write!(
+                    f,
+                    "line {} is {} cells wide, expected {} like the first line",
+                    line, found, expected
+                )
+            }
+        }
+    }
+}
+
+impl error::Error for Day4Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match *self {
+            Day4Error::InvalidTemplate(_) => None,
+            Day4Error::RaggedLine { .. } => None,
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use proptest::prelude::*;
+
     use super::*;
     use crate::test_init;
 
+    fn puzzle_from_chars(rows: Vec<Vec<char>>) -> Puzzle {
+        rows.into_iter()
+            .map(|row| row.into_iter().map(|cell| cell.to_string()).collect())
+            .collect()
+    }
+
     fn sample_puzzle_vectors() -> Puzzle {
-        vec![
+        puzzle_from_chars(vec![
             vec!['M', 'M', 'M', 'S', 'X', 'X', 'M', 'A', 'S', 'M'],
             vec!['M', 'S', 'A', 'M', 'X', 'M', 'S', 'M', 'S', 'A'],
             vec!['A', 'M', 'X', 'S', 'X', 'M', 'A', 'A', 'M', 'M'],
@@ -237,7 +659,7 @@ mod tests {
             vec!['S', 'A', 'X', 'A', 'M', 'A', 'S', 'A', 'A', 'A'],
             vec!['M', 'A', 'M', 'M', 'M', 'X', 'M', 'M', 'M', 'M'],
             vec!['M', 'X', 'M', 'X', 'A', 'X', 'M', 'A', 'S', 'X'],
-        ]
+        ])
     }
 
     #[test]
@@ -245,36 +667,48 @@ mod tests {
         test_init();
         assert_eq!(
             sample_puzzle_vectors(),
-            parse_file(Utf8PathBuf::from("./src/puzzle_inputs/day4_sample.txt"))
+            parse_file(Utf8PathBuf::from("./src/puzzle_inputs/day4_sample.txt")).unwrap()
         );
     }
 
+    fn count_xmas(directions: &[Direction]) -> usize {
+        WordSearch::new("XMAS", directions)
+            .search(&sample_puzzle_vectors())
+            .len()
+    }
+
     #[test]
     fn test_xmas_horizontal_search() {
         test_init();
-        assert_eq!(5, search_horizontally(sample_puzzle_vectors()))
+        assert_eq!(5, count_xmas(&[Direction::East, Direction::West]))
     }
 
     #[test]
     fn test_xmas_vertical_search() {
         test_init();
-        assert_eq!(3, search_vertically(sample_puzzle_vectors()))
+        assert_eq!(3, count_xmas(&[Direction::North, Direction::South]))
     }
 
     #[test]
     fn test_xmas_diagonal_search_right() {
         test_init();
-        assert_eq!(5, search_diagonally_right(sample_puzzle_vectors()))
+        assert_eq!(5, count_xmas(&[Direction::SouthEast, Direction::NorthWest]))
     }
     #[test]
     fn test_xmas_diagonal_search_left() {
         test_init();
-        assert_eq!(5, search_diagonally_left(sample_puzzle_vectors()))
+        assert_eq!(5, count_xmas(&[Direction::SouthWest, Direction::NorthEast]))
     }
     #[test]
     fn test_xmas_diagonal_search() {
         test_init();
-        assert_eq!(10, search_diagonally(sample_puzzle_vectors()))
+        assert_eq!(10, count_xmas(&DirectionSet::Diagonal.directions()))
+    }
+
+    #[test]
+    fn test_xmas_orthogonal_search() {
+        test_init();
+        assert_eq!(8, count_xmas(&DirectionSet::Orthogonal.directions()))
     }
 
     #[test]
@@ -282,14 +716,63 @@ mod tests {
         test_init();
         assert_eq!(
             18,
-            search_puzzle(Utf8PathBuf::from("./src/puzzle_inputs/day4_sample.txt"))
+            search_puzzle(
+                Utf8PathBuf::from("./src/puzzle_inputs/day4_sample.txt"),
+                "XMAS",
+                DirectionSet::All
+            )
+            .unwrap()
+            .1
+            .len()
         )
     }
 
+    #[test]
+    fn test_word_search_reports_start_and_direction() {
+        test_init();
+        let puzzle: Puzzle = puzzle_from_chars(vec![
+            vec!['S', 'A', 'M', 'X'],
+            vec!['.', '.', 'A', '.'],
+            vec!['.', '.', '.', 'M'],
+        ]);
+        assert_eq!(
+            vec![
+                WordMatch {
+                    x: 1,
+                    y: 0,
+                    direction: Direction::East,
+                    length: 2
+                },
+                WordMatch {
+                    x: 2,
+                    y: 1,
+                    direction: Direction::North,
+                    length: 2
+                },
+                WordMatch {
+                    x: 2,
+                    y: 1,
+                    direction: Direction::SouthEast,
+                    length: 2
+                },
+            ],
+            WordSearch::new("AM", &DirectionSet::All.directions()).search(&puzzle)
+        );
+        assert_eq!(
+            1,
+            WordSearch::new("XMAS", &[Direction::West])
+                .search(&puzzle)
+                .len()
+        );
+        assert!(WordSearch::new("", &DirectionSet::All.directions())
+            .search(&puzzle)
+            .is_empty());
+    }
+
     #[test]
     fn test_mas_search() {
         test_init();
-        assert_eq!(9, search_for_mas(sample_puzzle_vectors()))
+        assert_eq!(9, search_for_mas(&sample_puzzle_vectors()).len())
     }
 
     #[test]
@@ -298,6 +781,328 @@ mod tests {
         assert_eq!(
             9,
             search_puzzle_for_x_mas(Utf8PathBuf::from("./src/puzzle_inputs/day4_sample.txt"))
+                .unwrap()
+                .1
+                .len()
+        )
+    }
+
+    #[test]
+    fn test_parse_templates() {
+        test_init();
+        assert_eq!(
+            Ok(Template {
+                cells: vec![
+                    vec![Some("M".to_string()), None, Some("S".to_string())],
+                    vec![None, Some("A".to_string()), None],
+                ]
+            }),
+            "M.S/.A.".parse()
+        );
+        assert_eq!(
+            "M.S/.A./M.S",
+            X_MAS_TEMPLATE.parse::<Template>().unwrap().to_string()
+        );
+        for invalid in ["M.S/.A", "", "/"] {
+            assert_eq!(
+                Err(Day4Error::InvalidTemplate(invalid.to_owned())),
+                invalid.parse::<Template>()
+            );
+        }
+    }
+
+    #[test]
+    fn test_template_orientations() {
+        test_init();
+        let x_mas: Vec<String> = x_mas_templates()
+            .iter()
+            .map(|template| template.to_string())
+            .collect();
+        assert_eq!(
+            vec!["M.S/.A./M.S", "M.M/.A./S.S", "S.M/.A./S.M", "S.S/.A./M.M"],
+            x_mas
+        );
+
+        let corner: Template = "XM/A.".parse().unwrap();
+        assert_eq!(1, corner.orientations(false, false).len());
+        assert_eq!(2, corner.orientations(false, true).len());
+        assert_eq!(4, corner.orientations(true, false).len());
+        assert_eq!(8, corner.orientations(true, true).len());
+        let symmetric: Template = "A.A/.A./A.A".parse().unwrap();
+        assert_eq!(1, symmetric.orientations(true, true).len());
+    }
+
+    #[test]
+    fn test_search_templates_reports_top_left_corner() {
+        test_init();
+        let templates = "XMAS".parse::<Template>().unwrap().orientations(true, true);
+        let matches = search_templates(&sample_puzzle_vectors(), &templates);
+        assert_eq!(8, matches.len());
+        assert!(matches.contains(&TemplateMatch {
+            x: 9,
+            y: 3,
+            template: "X/M/A/S".parse().unwrap()
+        }));
+    }
+
+    #[test]
+    fn test_load_templates_from_file_and_cli() {
+        test_init();
+        let templates = load_templates(
+            &["M.S/.A./M.S".parse().unwrap()],
+            Some(Utf8PathBuf::from(
+                "./src/puzzle_inputs/day4_sample_templates.txt",
+            )),
         )
+        .unwrap();
+        assert_eq!(3, templates.len());
+        assert_eq!(
+            9,
+            search_puzzle_for_templates(
+                Utf8PathBuf::from("./src/puzzle_inputs/day4_sample.txt"),
+                &templates[..1],
+                true,
+                false
+            )
+            .unwrap()
+            .1
+            .len()
+        );
+    }
+
+    #[test]
+    fn test_highlight_xmas_sample() {
+        test_init();
+        let (puzzle, matches) = search_puzzle(
+            Utf8PathBuf::from("./src/puzzle_inputs/day4_sample.txt"),
+            "XMAS",
+            DirectionSet::All,
+        )
+        .unwrap();
+        assert_eq!(
+            [
+                "....XXMAS.",
+                ".SAMXMS...",
+                "...S..A...",
+                "..A.A.MS.X",
+                "XMASAMX.MM",
+                "X.....XA.A",
+                "S.S.S.S.SS",
+                ".A.A.A.A.A",
+                "..M.M.M.MM",
+                ".X.X.XMASX",
+            ]
+            .join("\n"),
+            highlight_matches(&puzzle, &matches, false)
+        );
+    }
+
+    #[test]
+    fn test_highlight_x_mas_sample() {
+        test_init();
+        let (puzzle, matches) =
+            search_puzzle_for_x_mas(Utf8PathBuf::from("./src/puzzle_inputs/day4_sample.txt"))
+                .unwrap();
+        assert_eq!(
+            [
+                ".M.S......",
+                "..A..MSMS.",
+                ".M.S.MAA..",
+                "..A.ASMSM.",
+                ".M.S.M....",
+                "..........",
+                "S.S.S.S.S.",
+                ".A.A.A.A..",
+                "M.M.M.M.M.",
+                "..........",
+            ]
+            .join("\n"),
+            highlight_matches(&puzzle, &matches, false)
+        );
+    }
+
+    #[test]
+    fn test_highlight_colors_each_match() {
+        test_init();
+        let puzzle: Puzzle = puzzle_from_chars(vec![vec!['A', 'B', 'A', 'C']]);
+        let matches = WordSearch::new("AB", &DirectionSet::Orthogonal.directions()).search(&puzzle);
+        assert_eq!(
+            "\x1b[31mA\x1b[0m\x1b[1mB\x1b[0m\x1b[32mA\x1b[0m.",
+            highlight_matches(&puzzle, &matches, true)
+        );
+    }
+
+    #[test]
+    fn test_parse_reports_first_ragged_line() {
+        test_init();
+        assert_eq!(
+            Err(Day4Error::RaggedLine {
+                line: 3,
+                expected: 4,
+                found: 3
+            }),
+            parse_puzzle("XMAS\nSAMX\nXMA\nXM")
+        );
+        assert_eq!(
+            Ok(puzzle_from_chars(vec![vec!['X', 'M'], vec!['A', 'S']])),
+            parse_puzzle("XM\r\nAS\n")
+        );
+    }
+
+    #[test]
+    fn test_parse_ignores_trailing_blank_lines() {
+        test_init();
+        assert_eq!(
+            Ok(puzzle_from_chars(vec![
+                vec!['X', 'M', 'A', 'S'],
+                vec!['M', 'A', 'S', 'X']
+            ])),
+            parse_puzzle("XMAS\nMASX\n\n")
+        );
+        assert_eq!(
+            Err(Day4Error::RaggedLine {
+                line: 2,
+                expected: 4,
+                found: 0
+            }),
+            parse_puzzle("XMAS\n\nMASX\n")
+        );
+    }
+
+    #[test]
+    fn test_word_search_over_grapheme_cells() {
+        test_init();
+        // "é" written as 'e' plus a combining accent is still a single cell
+        let puzzle = parse_puzzle("СЛОВО\nЛé..Л\nО.Оe\u{301}О").unwrap();
+        assert_eq!(5, puzzle[2].len());
+        assert_eq!(
+            vec![WordMatch {
+                x: 0,
+                y: 0,
+                direction: Direction::South,
+                length: 3
+            },],
+            WordSearch::new("СЛО", &DirectionSet::Orthogonal.directions())
+                .search(&puzzle)
+                .into_iter()
+                .filter(|word_match| word_match.direction == Direction::South)
+                .collect::<Vec<WordMatch>>()
+        );
+        // Only the decomposed "é" on the last line, the precomposed one on the second line is a different letter
+        assert_eq!(
+            2,
+            WordSearch::new("e\u{301}О", &DirectionSet::All.directions())
+                .search(&puzzle)
+                .len()
+        );
+        let template: Template = "С.О/.é./О.О".parse().unwrap();
+        assert_eq!(3, template.width());
+        assert_eq!(
+            1,
+            search_templates(&puzzle, &template.orientations(false, false)).len()
+        );
+    }
+
+    // Reads the word's length of cells from every start cell in every direction, as (x, y, delta) triples
+    fn oracle_word_search(
+        puzzle: &Puzzle,
+        word: &str,
+        directions: &[Direction],
+    ) -> Vec<(usize, usize, (isize, isize))> {
+        let letters: Vec<char> = word.chars().collect();
+        let mut found = vec![];
+        for y in 0..puzzle.len() {
+            for x in 0..puzzle[y].len() {
+                for direction in directions {
+                    let (delta_x, delta_y) = direction.delta();
+                    let read: Option<String> = (0..letters.len() as isize)
+                        .map(|offset| {
+                            let cell_x = x as isize + delta_x * offset;
+                            let cell_y = y as isize + delta_y * offset;
+                            if cell_x < 0 || cell_y < 0 {
+                                return None;
+                            }
+                            puzzle
+                                .get(cell_y as usize)
+                                .and_then(|line| line.get(cell_x as usize))
+                                .cloned()
+                        })
+                        .collect();
+                    if !letters.is_empty() && read.as_deref() == Some(word) {
+                        found.push((x, y, (delta_x, delta_y)));
+                    }
+                }
+            }
+        }
+        found.sort();
+        found
+    }
+
+    // An X-MAS is an A with M and S at opposite ends of both its diagonals, reported by its top left corner
+    fn oracle_x_mas(puzzle: &Puzzle) -> Vec<(usize, usize)> {
+        let mut found = vec![];
+        for y in 1..puzzle.len().saturating_sub(1) {
+            for x in 1..puzzle[y].len().saturating_sub(1) {
+                let cell = |x: usize, y: usize| puzzle[y][x].as_str();
+                let diagonal = |first: &str, second: &str| {
+                    (first == "M" && second == "S") || (first == "S" && second == "M")
+                };
+                if cell(x, y) == "A"
+                    && diagonal(cell(x - 1, y - 1), cell(x + 1, y + 1))
+                    && diagonal(cell(x + 1, y - 1), cell(x - 1, y + 1))
+                {
+                    found.push((x - 1, y - 1));
+                }
+            }
+        }
+        found
+    }
+
+    // Square or rectangular grids over a small alphabet, so words turn up often
+    fn puzzle_strategy() -> impl Strategy<Value = Puzzle> {
+        (1usize..9, 1usize..9).prop_flat_map(|(width, height)| {
+            proptest::collection::vec(
+                proptest::collection::vec(
+                    proptest::sample::select(vec!['X', 'M', 'A', 'S']),
+                    width,
+                ),
+                height,
+            )
+            .prop_map(puzzle_from_chars)
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn prop_word_search_matches_oracle(
+            puzzle in puzzle_strategy(),
+            word in "[XMAS]{0,4}",
+            direction_set in prop_oneof![
+                Just(DirectionSet::All),
+                Just(DirectionSet::Orthogonal),
+                Just(DirectionSet::Diagonal)
+            ]
+        ) {
+            let directions = direction_set.directions();
+            let mut found: Vec<(usize, usize, (isize, isize))> = WordSearch::new(&word, &directions)
+                .search(&puzzle)
+                .iter()
+                .map(|word_match| (word_match.x, word_match.y, word_match.direction.delta()))
+                .collect();
+            found.sort();
+            prop_assert_eq!(oracle_word_search(&puzzle, &word, &directions), found);
+        }
+
+        #[test]
+        fn prop_x_mas_search_matches_oracle(puzzle in puzzle_strategy()) {
+            let mut found: Vec<(usize, usize)> = search_for_mas(&puzzle)
+                .iter()
+                .map(|template_match| (template_match.x, template_match.y))
+                .collect();
+            found.sort();
+            let mut expected = oracle_x_mas(&puzzle);
+            expected.sort();
+            prop_assert_eq!(expected, found);
+        }
     }
 }
@@ -92,11 +92,14 @@ fn validate_update_order(update: &Update, rules: &Vec<Rule>) -> bool {
 fn calculate_middle_page_total(file_path: Utf8PathBuf, invalid_updates: bool) -> i32 {
     info!("Calculating Valid Updates middle page number total");
     let (rules, updates) = parse_file(file_path);
+    middle_page_total(&rules, updates, invalid_updates)
+}
 
+fn middle_page_total(rules: &Vec<Rule>, updates: Vec<Update>, invalid_updates: bool) -> i32 {
     let mut count = 0;
 
     for update in updates {
-        if validate_update_order(&update, &rules) {
+        if validate_update_order(&update, rules) {
             if !invalid_updates {
                 info!("Update is Valid, update '{:?}'", update);
                 let middle_page_num = update[(update.len() - 1) / 2];
@@ -106,10 +109,10 @@ fn calculate_middle_page_total(file_path: Utf8PathBuf, invalid_updates: bool) ->
         } else {
             if invalid_updates {
                 info!("Update is not valid, reordering update according to rules");
-                let ordered_update = order_incorrect_update(&update, &rules);
+                let ordered_update = order_incorrect_update(&update, rules);
                 debug!(
                     "Valid order: {}",
-                    validate_update_order(&ordered_update, &rules)
+                    validate_update_order(&ordered_update, rules)
                 );
                 let middle_page_num = ordered_update[(ordered_update.len() - 1) / 2];
                 info!("Adding Middle page number to count {}", middle_page_num);
@@ -418,6 +421,8 @@ fn order_incorrect_update(update: &Update, rules: &Vec<Rule>) -> Update {
 #[cfg(test)]
 mod tests {
 
+    use proptest::prelude::*;
+
     use super::*;
     use crate::test_init;
 
@@ -600,4 +605,94 @@ mod tests {
             )
         )
     }
+
+    // An update is in order if no rule puts a later page before an earlier one
+    fn oracle_in_order(update: &[i32], rules: &[Rule]) -> bool {
+        (0..update.len()).all(|earlier| {
+            (earlier + 1..update.len()).all(|later| {
+                !rules
+                    .iter()
+                    .any(|rule| rule[0] == update[later] && rule[1] == update[earlier])
+            })
+        })
+    }
+
+    fn permutations(pages: &[i32]) -> Vec<Vec<i32>> {
+        if pages.is_empty() {
+            return vec![vec![]];
+        }
+        let mut orders = vec![];
+        for (index, page) in pages.iter().enumerate() {
+            let mut remaining = pages.to_vec();
+            remaining.remove(index);
+            for mut order in permutations(&remaining) {
+                order.insert(0, *page);
+                orders.push(order);
+            }
+        }
+        orders
+    }
+
+    // Tries every ordering of the update, with a total order exactly one of them follows the rules
+    fn oracle_reorder(update: &[i32], rules: &[Rule]) -> Vec<i32> {
+        permutations(update)
+            .into_iter()
+            .find(|order| oracle_in_order(order, rules))
+            .expect("a total order to allow one ordering")
+    }
+
+    fn oracle_middle_page_total(rules: &[Rule], updates: &[Update], invalid_updates: bool) -> i32 {
+        updates
+            .iter()
+            .filter(|update| oracle_in_order(update, rules) != invalid_updates)
+            .map(|update| {
+                let ordered_update = oracle_reorder(update, rules);
+                ordered_update[(ordered_update.len() - 1) / 2]
+            })
+            .sum()
+    }
+
+    // Pages in a random total order, a rule for every pair of them in a random order, and updates of distinct pages
+    fn rules_and_updates_strategy() -> impl Strategy<Value = (Vec<Rule>, Vec<Update>)> {
+        proptest::sample::subsequence((10..100).collect::<Vec<i32>>(), 2..7)
+            .prop_shuffle()
+            .prop_flat_map(|order| {
+                let mut rules: Vec<Rule> = vec![];
+                for (index, page) in order.iter().enumerate() {
+                    for later_page in &order[index + 1..] {
+                        rules.push(vec![*page, *later_page]);
+                    }
+                }
+                let update =
+                    proptest::sample::subsequence(order.clone(), 1..=order.len()).prop_shuffle();
+                (
+                    Just(rules).prop_shuffle(),
+                    proptest::collection::vec(update, 1..6),
+                )
+            })
+    }
+
+    proptest! {
+        #[test]
+        fn prop_update_order_matches_oracle((rules, updates) in rules_and_updates_strategy()) {
+            for update in &updates {
+                let in_order = oracle_in_order(update, &rules);
+                prop_assert_eq!(in_order, validate_update_order(update, &rules));
+                if !in_order {
+                    prop_assert_eq!(oracle_reorder(update, &rules), order_incorrect_update(update, &rules));
+                }
+            }
+        }
+
+        #[test]
+        fn prop_middle_page_total_matches_oracle(
+            (rules, updates) in rules_and_updates_strategy(),
+            invalid_updates: bool
+        ) {
+            prop_assert_eq!(
+                oracle_middle_page_total(&rules, &updates, invalid_updates),
+                middle_page_total(&rules, updates, invalid_updates)
+            );
+        }
+    }
 }
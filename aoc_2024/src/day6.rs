@@ -332,10 +332,13 @@ fn simulate_patrol(
 }
 
 fn count_distinct_cells(file_path: Utf8PathBuf, simulation_limit: i32) -> usize {
-    let map = parse_file(file_path);
+    count_map_distinct_cells(parse_file(file_path), simulation_limit)
+        .expect("expected map to be simulated correctly")
+}
+
+fn count_map_distinct_cells(map: Map, simulation_limit: i32) -> Result<usize, Day6Error> {
     let mut guard = find_guard(&map).expect("guard to exist in map");
-    let guard_positions = simulate_patrol(map, &mut guard, simulation_limit, false)
-        .expect("expected map to be simulated correctly");
+    let guard_positions = simulate_patrol(map, &mut guard, simulation_limit, false)?;
 
     let mut unique_positions: HashMap<Position, Guard> = HashMap::new();
     for (_unique_position, unique_guard) in guard_positions {
@@ -347,11 +350,14 @@ fn count_distinct_cells(file_path: Utf8PathBuf, simulation_limit: i32) -> usize
             unique_guard,
         );
     }
-    unique_positions.len()
+    Ok(unique_positions.len())
 }
 
 fn test_obstructions(file_path: Utf8PathBuf, simulation_limit: i32) -> usize {
-    let map = parse_file(file_path);
+    count_map_obstructions(parse_file(file_path), simulation_limit)
+}
+
+fn count_map_obstructions(map: Map, simulation_limit: i32) -> usize {
     let mut guard = find_guard(&map).expect("guard to exist in map");
     let obstruction_guard = guard.clone();
     let guard_positions = simulate_patrol(map.clone(), &mut guard, simulation_limit, false)
@@ -436,6 +442,10 @@ fn test_obstructions(file_path: Utf8PathBuf, simulation_limit: i32) -> usize {
 
 #[cfg(test)]
 mod tests {
+    use std::collections::HashSet;
+
+    use proptest::prelude::*;
+
     use super::*;
     use crate::test_init;
 
@@ -575,4 +585,140 @@ mod tests {
             )
         )
     }
+
+    // Walks the guard one step at a time on a grid of obstruction flags, giving the cells visited or None if the
+    // guard ever comes back to the same cell facing the same way
+    fn oracle_patrol(
+        obstructions: &[Vec<bool>],
+        start: (usize, usize),
+        facing: usize,
+    ) -> Option<HashSet<(usize, usize)>> {
+        const STEPS: [(i64, i64); 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+        let (mut x, mut y, mut facing) = (start.0 as i64, start.1 as i64, facing);
+        let mut states = HashSet::new();
+        let mut cells = HashSet::new();
+        loop {
+            if !states.insert((x, y, facing)) {
+                return None;
+            }
+            cells.insert((x as usize, y as usize));
+            let (next_x, next_y) = (x + STEPS[facing].0, y + STEPS[facing].1);
+            if next_y < 0
+                || next_x < 0
+                || next_y as usize >= obstructions.len()
+                || next_x as usize >= obstructions[0].len()
+            {
+                return Some(cells);
+            }
+            if obstructions[next_y as usize][next_x as usize] {
+                facing = (facing + 1) % 4;
+            } else {
+                (x, y) = (next_x, next_y);
+            }
+        }
+    }
+
+    // Tries an obstruction in every free cell other than the guard's
+    fn oracle_obstructions(
+        obstructions: &[Vec<bool>],
+        start: (usize, usize),
+        facing: usize,
+    ) -> usize {
+        let mut count = 0;
+        for y in 0..obstructions.len() {
+            for x in 0..obstructions[0].len() {
+                if obstructions[y][x] || (x, y) == start {
+                    continue;
+                }
+                let mut obstructed = obstructions.to_vec();
+                obstructed[y][x] = true;
+                if oracle_patrol(&obstructed, start, facing).is_none() {
+                    count += 1;
+                }
+            }
+        }
+        count
+    }
+
+    // A grid of obstruction flags with exactly one guard, given as its position and which way it faces
+    fn guard_grid_strategy() -> impl Strategy<Value = (Vec<Vec<bool>>, (usize, usize), usize)> {
+        (1usize..8, 1usize..8).prop_flat_map(|(width, height)| {
+            (
+                proptest::collection::vec(
+                    proptest::collection::vec(proptest::bool::weighted(0.25), width),
+                    height,
+                ),
+                (0..width, 0..height),
+                0usize..4,
+            )
+                .prop_map(|(mut obstructions, start, facing)| {
+                    obstructions[start.1][start.0] = false;
+                    (obstructions, start, facing)
+                })
+        })
+    }
+
+    fn guard_map(obstructions: &[Vec<bool>], start: (usize, usize), facing: usize) -> Map {
+        let mut map = Map::new(
+            obstructions
+                .iter()
+                .map(|row| {
+                    row.iter()
+                        .map(|obstruction| {
+                            if *obstruction {
+                                Entity::Obstruction
+                            } else {
+                                Entity::Empty
+                            }
+                        })
+                        .collect()
+                })
+                .collect(),
+        );
+        let direction = [
+            Direction::North,
+            Direction::East,
+            Direction::South,
+            Direction::West,
+        ][facing]
+            .clone();
+        map.grid[start.1][start.0] = Entity::Guard(Guard {
+            direction,
+            x: start.0,
+            y: start.1,
+        });
+        map
+    }
+
+    // Enough iterations for the guard to pass through every cell facing every way
+    fn simulation_limit(obstructions: &[Vec<bool>]) -> i32 {
+        (4 * obstructions.len() * obstructions[0].len() + 1) as i32
+    }
+
+    proptest! {
+        #[test]
+        fn prop_distinct_cells_match_oracle((obstructions, start, facing) in guard_grid_strategy()) {
+            let expected = oracle_patrol(&obstructions, start, facing).map(|cells| cells.len());
+            let counted = count_map_distinct_cells(
+                guard_map(&obstructions, start, facing),
+                simulation_limit(&obstructions),
+            );
+            match expected {
+                Some(cells) => prop_assert_eq!(Ok(cells), counted),
+                None => prop_assert_eq!(Err(Day6Error::ExceededLimit), counted),
+            }
+        }
+
+        #[test]
+        fn prop_obstructions_match_oracle((obstructions, start, facing) in guard_grid_strategy()) {
+            prop_assume!(oracle_patrol(&obstructions, start, facing).is_some());
+            prop_assert_eq!(
+                oracle_obstructions(&obstructions, start, facing),
+                count_map_obstructions(
+                    guard_map(&obstructions, start, facing),
+                    simulation_limit(&obstructions),
+                )
+            );
+        }
+    }
 }
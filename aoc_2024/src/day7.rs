@@ -1,7 +1,16 @@
-use std::fmt::Display;
+use std::{
+    error,
+    fmt::{self, Debug, Display},
+    num::{IntErrorKind, NonZeroUsize, ParseIntError},
+    str::FromStr,
+    sync::atomic::{AtomicUsize, Ordering},
+    thread,
+    time::{Duration, Instant},
+};
 
 use camino::Utf8PathBuf;
 use clap::Subcommand;
+use num_traits::{checked_pow, CheckedRem, NumCast, PrimInt, Signed};
 
 use crate::read_file;
 
@@ -15,27 +24,199 @@ pub enum Day7Commands {
         /// Whether to calculate with concatenation operators
         #[arg(short, long, default_value_t = false)]
         concatenate: bool,
+        /// Comma separated operators to calculate with, e.g. "+,*,||,-,/,^"
+        #[arg(long, value_delimiter = ',', conflicts_with = "concatenate")]
+        operators: Option<Vec<Operator>>,
+        /// Whether to calculate with 128 bit integers, for inputs that overflow 64 bits
+        #[arg(long, default_value_t = false)]
+        wide: bool,
+        /// Number of threads to solve equations across, defaults to the available parallelism
+        #[arg(long)]
+        threads: Option<usize>,
+        /// Number of slowest equations to report timings for
+        #[arg(long, default_value_t = 5)]
+        slowest: usize,
+    },
+    /// Prints each solvable equation with the operators that solve it
+    Show {
+        /// Input File Path
+        #[arg(short, long)]
+        path: Utf8PathBuf,
+        /// Whether to calculate with concatenation operators
+        #[arg(short, long, default_value_t = false)]
+        concatenate: bool,
+        /// Comma separated operators to calculate with, e.g. "+,*,||,-,/,^"
+        #[arg(long, value_delimiter = ',', conflicts_with = "concatenate")]
+        operators: Option<Vec<Operator>>,
+        /// Whether to print every operator combination that solves an equation, rather than just the first found
+        #[arg(long, default_value_t = false)]
+        all_solutions: bool,
+        /// Whether to calculate with 128 bit integers, for inputs that overflow 64 bits
+        #[arg(long, default_value_t = false)]
+        wide: bool,
+        /// Number of threads to solve equations across, defaults to the available parallelism
+        #[arg(long)]
+        threads: Option<usize>,
     },
 }
 
 pub fn day7_cli_command_processing(command: &Day7Commands) {
     match command {
-        Day7Commands::Calculate { path, concatenate } => {
+        Day7Commands::Calculate {
+            path,
+            concatenate,
+            operators,
+            wide,
+            threads,
+            slowest,
+        } => {
             info!("Command received to calculate total sum from valid equations");
+            let operators = operators
+                .clone()
+                .unwrap_or_else(|| default_operators(concatenate.to_owned()));
+            let threads = threads.unwrap_or_else(default_threads);
+            if *wide {
+                print_total(calculate_total_equations_result::<i128, _>(
+                    path.clone(),
+                    &operators,
+                    threads,
+                    slowest.to_owned(),
+                ));
+            } else {
+                print_total(calculate_total_equations_result::<i64, _>(
+                    path.clone(),
+                    &operators,
+                    threads,
+                    slowest.to_owned(),
+                ));
+            }
+        }
+        Day7Commands::Show {
+            path,
+            concatenate,
+            operators,
+            all_solutions,
+            wide,
+            threads,
+        } => {
+            info!("Command received to show solved equations");
+            let operators = operators
+                .clone()
+                .unwrap_or_else(|| default_operators(concatenate.to_owned()));
+            let threads = threads.unwrap_or_else(default_threads);
+            if *wide {
+                print_total(show_solved_equations::<i128>(
+                    path.clone(),
+                    &operators,
+                    all_solutions.to_owned(),
+                    threads,
+                ));
+            } else {
+                print_total(show_solved_equations::<i64>(
+                    path.clone(),
+                    &operators,
+                    all_solutions.to_owned(),
+                    threads,
+                ));
+            }
+        }
+    }
+}
+
+fn show_solved_equations<N: EquationValue>(
+    file_path: Utf8PathBuf,
+    operators: &[Operator],
+    all_solutions: bool,
+    threads: usize,
+) -> Result<EquationsResult<N>, Day7Error> {
+    let solved_equations = solve_equations::<N, _>(file_path, operators, all_solutions, threads)?;
+    for (equation, equation_solutions, _elapsed) in &solved_equations {
+        for solution in &equation_solutions.solutions {
             println!(
-                "Total Sum from valid equations: {}",
-                calculate_total_equations_result(path.clone(), concatenate.to_owned())
+                "{}",
+                SolvedEquation {
+                    equation,
+                    operators: solution
+                }
             );
         }
     }
+    total_solved_equations(&solved_equations)
+}
+
+fn default_threads() -> usize {
+    thread::available_parallelism()
+        .map(NonZeroUsize::get)
+        .unwrap_or(1)
 }
 
-type Equation = (i64, Vec<i64>);
-#[derive(Clone, Debug)]
-enum Operator {
+fn print_total<N: EquationValue>(result: Result<EquationsResult<N>, Day7Error>) {
+    match result {
+        Ok(result) => {
+            if result.overflow_warnings > 0 {
+                warn!(
+                    "{} operator branches overflowed and were treated as not matching, the total may be missing equations. Try --wide",
+                    result.overflow_warnings
+                );
+            }
+            println!("Total Sum from valid equations: {}", result.total);
+        }
+        Err(error) => error!(
+            "Unable to calculate total sum from valid equations: {}",
+            error
+        ),
+    }
+}
+
+// Integer types equations can be solved over, i.e. i64 or i128 with --wide
+trait EquationValue:
+    PrimInt + Signed + CheckedRem + FromStr<Err = ParseIntError> + Display + Debug + Send + Sync
+{
+}
+
+impl<N> EquationValue for N where
+    N: PrimInt + Signed + CheckedRem + FromStr<Err = ParseIntError> + Display + Debug + Send + Sync
+{
+}
+
+type Equation<N> = (N, Vec<N>);
+type Solution<O> = Vec<O>;
+// Each equation in file order, with its solutions and how long it took to solve
+type SolvedEquations<N, O> = Vec<(Equation<N>, EquationSolutions<O>, Duration)>;
+
+// Why an operator couldn't be applied, overflows are tracked separately as they may hide a valid solution
+#[derive(Debug, PartialEq)]
+enum BranchFailure {
+    Overflow,
+    Undefined,
+}
+
+// Anything that can sit between two values of an equation. Implement this to search with operators beyond the built in ones.
+trait EquationOperator<N: EquationValue>: Clone + Debug + Display + Send + Sync {
+    // Evaluates `left <operator> right`
+    fn apply(&self, left: N, right: N) -> Result<N, BranchFailure>;
+
+    // Candidate values for `left` such that `left <operator> right == target`. The solver checks each candidate with `apply`,
+    // so this can over-approximate. None means the candidates can't be narrowed down (e.g. multiplying by zero),
+    // in which case the solver evaluates the remaining values left to right instead.
+    fn undo(&self, _target: N, _right: N) -> Result<Option<Vec<N>>, BranchFailure> {
+        Ok(None)
+    }
+
+    // Whether non-negative operands always give a non-negative result, which lets the solver discard negative targets
+    fn preserves_non_negative(&self) -> bool {
+        false
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Operator {
     Multiply,
     Add,
     Concatenation,
+    Subtract,
+    Divide,
+    Exponent,
 }
 
 impl Display for Operator {
@@ -44,79 +225,267 @@ impl Display for Operator {
             Operator::Add => write!(f, "+"),
             Operator::Multiply => write!(f, "*"),
             Operator::Concatenation => write!(f, "||"),
+            Operator::Subtract => write!(f, "-"),
+            Operator::Divide => write!(f, "/"),
+            Operator::Exponent => write!(f, "^"),
+        }
+    }
+}
+
+impl FromStr for Operator {
+    type Err = Day7Error;
+
+    fn from_str(operator: &str) -> Result<Self, Self::Err> {
+        match operator.trim() {
+            "+" => Ok(Operator::Add),
+            "*" => Ok(Operator::Multiply),
+            "||" => Ok(Operator::Concatenation),
+            "-" => Ok(Operator::Subtract),
+            "/" => Ok(Operator::Divide),
+            "^" => Ok(Operator::Exponent),
+            unknown => Err(Day7Error::UnknownOperator(unknown.to_owned())),
         }
     }
 }
 
-fn parse_file(file_path: Utf8PathBuf) -> Vec<Equation> {
+impl<N: EquationValue> EquationOperator<N> for Operator {
+    fn apply(&self, left: N, right: N) -> Result<N, BranchFailure> {
+        match self {
+            Operator::Add => left.checked_add(&right).ok_or(BranchFailure::Overflow),
+            Operator::Multiply => left.checked_mul(&right).ok_or(BranchFailure::Overflow),
+            Operator::Concatenation => concatenate(left, right),
+            Operator::Subtract => left.checked_sub(&right).ok_or(BranchFailure::Overflow),
+            // Only exact division counts, anything with a remainder is a failed branch
+            Operator::Divide => {
+                if right.is_zero() || !left.checked_rem(&right).is_some_and(|rem| rem.is_zero()) {
+                    return Err(BranchFailure::Undefined);
+                }
+                left.checked_div(&right).ok_or(BranchFailure::Overflow)
+            }
+            Operator::Exponent => {
+                let exponent = right.to_usize().ok_or(BranchFailure::Undefined)?;
+                checked_pow(left, exponent).ok_or(BranchFailure::Overflow)
+            }
+        }
+    }
+
+    fn undo(&self, target: N, right: N) -> Result<Option<Vec<N>>, BranchFailure> {
+        let candidate = match self {
+            Operator::Add => target.checked_sub(&right),
+            Operator::Multiply => {
+                if right.is_zero() {
+                    // Anything multiplied by zero is zero, so the left hand side could be any value
+                    return Ok((!target.is_zero()).then(Vec::new));
+                }
+                if !target.checked_rem(&right).is_some_and(|rem| rem.is_zero()) {
+                    return Ok(Some(vec![]));
+                }
+                target.checked_div(&right)
+            }
+            Operator::Concatenation => {
+                let multiplier = digit_multiplier(right)?;
+                // Concatenating onto a negative number subtracts, i.e. "-5" + "3" is -53
+                let shifted = if target < N::zero() {
+                    target.checked_add(&right)
+                } else {
+                    target.checked_sub(&right)
+                }
+                .ok_or(BranchFailure::Overflow)?;
+                if !(shifted % multiplier).is_zero() {
+                    return Ok(Some(vec![]));
+                }
+                Some(shifted / multiplier)
+            }
+            Operator::Subtract => target.checked_add(&right),
+            Operator::Divide => target.checked_mul(&right),
+            Operator::Exponent => {
+                if right.is_zero() {
+                    // Anything to the power of zero is one
+                    return Ok((!target.is_one()).then(Vec::new));
+                }
+                // Same conversion as `apply`, so an exponent it can't raise to has no candidates either
+                let Some(exponent) = right.to_usize() else {
+                    return Ok(Some(vec![]));
+                };
+                if exponent == 1 {
+                    return Ok(Some(vec![target]));
+                }
+                // Either sign of the root could be the one, the checked `apply` picks out which
+                let root = integer_root(target, exponent);
+                let mut roots = vec![-root, root];
+                roots.dedup();
+                return Ok(Some(roots));
+            }
+        };
+        candidate
+            .map(|candidate| Some(vec![candidate]))
+            .ok_or(BranchFailure::Overflow)
+    }
+
+    fn preserves_non_negative(&self) -> bool {
+        !matches!(self, Operator::Subtract)
+    }
+}
+
+fn default_operators(concat: bool) -> Vec<Operator> {
+    if concat {
+        vec![Operator::Add, Operator::Multiply, Operator::Concatenation]
+    } else {
+        vec![Operator::Add, Operator::Multiply]
+    }
+}
+
+struct SolvedEquation<'a, N, O> {
+    equation: &'a Equation<N>,
+    operators: &'a [O],
+}
+
+impl<N: Display, O: Display> Display for SolvedEquation<'_, N, O> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (total, values) = self.equation;
+        write!(f, "{}: {}", total, values[0])?;
+        for (value, operator) in values[1..].iter().zip(self.operators) {
+            write!(f, " {} {}", operator, value)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct EquationSolutions<O> {
+    solutions: Vec<Solution<O>>,
+    // Number of branches pruned because they overflowed, any of which could have been a solution with wider integers
+    overflow_warnings: usize,
+}
+
+#[derive(Debug, PartialEq)]
+struct EquationsResult<N> {
+    total: N,
+    overflow_warnings: usize,
+}
+
+fn parse_value<N: EquationValue>(value: &str) -> Result<N, Day7Error> {
+    value.parse::<N>().map_err(|error| match error.kind() {
+        IntErrorKind::PosOverflow | IntErrorKind::NegOverflow => {
+            Day7Error::ValueOverflow(value.to_owned())
+        }
+        _ => Day7Error::InvalidValue(value.to_owned()),
+    })
+}
+
+fn parse_file<N: EquationValue>(file_path: Utf8PathBuf) -> Result<Vec<Equation<N>>, Day7Error> {
     let mut equations = vec![];
 
     let content = read_file(file_path);
     let equation_strings = content.split("\n");
     for equation in equation_strings {
         let parts: Vec<&str> = equation.split(": ").collect();
-        let total = parts[0].parse::<i64>().expect("To be a valid number");
-        let values: Vec<i64> = parts[1]
+        let total = parse_value(parts[0])?;
+        let values: Vec<N> = parts[1]
             .split(" ")
-            .map(|value| value.parse::<i64>().expect("To be a valid number"))
-            .collect();
+            .map(parse_value)
+            .collect::<Result<Vec<N>, Day7Error>>()?;
         equations.push((total, values));
     }
 
-    equations
+    Ok(equations)
+}
+
+// Numeric equivalent of joining the digits, so 15 || 6 is 156. Negative right hand values can't be concatenated.
+fn concatenate<N: EquationValue>(left: N, right: N) -> Result<N, BranchFailure> {
+    if right < N::zero() {
+        return Err(BranchFailure::Undefined);
+    }
+    let shifted = left
+        .checked_mul(&digit_multiplier(right)?)
+        .ok_or(BranchFailure::Overflow)?;
+    if left < N::zero() {
+        shifted.checked_sub(&right)
+    } else {
+        shifted.checked_add(&right)
+    }
+    .ok_or(BranchFailure::Overflow)
+}
+
+// Smallest power of ten greater than the value, i.e. how far `left` has to be shifted to concatenate `value` onto it
+fn digit_multiplier<N: EquationValue>(value: N) -> Result<N, BranchFailure> {
+    let ten: N = <N as NumCast>::from(10).expect("ten to fit in any integer type");
+    let mut multiplier = ten;
+    while multiplier <= value {
+        multiplier = multiplier
+            .checked_mul(&ten)
+            .ok_or(BranchFailure::Overflow)?;
+    }
+    Ok(multiplier)
+}
+
+// Largest value whose exponent-th power doesn't exceed the target's magnitude, found with a binary search over
+// checked powers since floating point is too coarse for 128 bit targets
+fn integer_root<N: EquationValue>(target: N, exponent: usize) -> N {
+    let magnitude = if target == N::min_value() {
+        N::max_value()
+    } else {
+        target.abs()
+    };
+    let (mut low, mut high) = (N::zero(), magnitude);
+    let two = N::one() + N::one();
+    while low < high {
+        // Rounding the midpoint up keeps the search moving once low and high are adjacent
+        let gap = high - low;
+        let middle = low + gap / two + gap % two;
+        if checked_pow(middle, exponent).is_some_and(|power| power <= magnitude) {
+            low = middle;
+        } else {
+            high = middle - N::one();
+        }
+    }
+    low
 }
 
-fn test_equation((total, values): &Equation, operators: &Vec<Operator>) -> bool {
+// Evaluates strictly left to right, an operator failing (e.g. overflowing) means the equation doesn't hold
+fn test_equation<N: EquationValue, O: EquationOperator<N>>(
+    (total, values): &Equation<N>,
+    operators: &[O],
+) -> bool {
     debug!(
         "Testing Equation '{:?}' with Operators {:?}",
         (total, values),
         operators
     );
-    let mut calculated_total = values[0];
-    let mut operator_index = 0;
-    for value in values[1..values.len()].iter() {
-        match operators[operator_index] {
-            Operator::Add => {
-                calculated_total = calculated_total + value;
-            }
-            Operator::Multiply => {
-                calculated_total = calculated_total * value;
-            }
-            Operator::Concatenation => {
-                calculated_total = (calculated_total.to_string() + &value.to_string())
-                    .parse::<i64>()
-                    .expect("to be a number");
-            }
-        }
-        operator_index += 1;
+    let mut calculated_total = Ok(values[0]);
+    for (value, operator) in values[1..].iter().zip(operators) {
+        calculated_total =
+            calculated_total.and_then(|running_total| operator.apply(running_total, *value));
     }
 
     debug!(
-        "Calculated Total: {}, Target Total: {}",
+        "Calculated Total: {:?}, Target Total: {}",
         calculated_total, total
     );
-    &calculated_total == total
+    calculated_total == Ok(*total)
 }
 
+// Original left to right brute force, kept around to check the reverse search against
+#[cfg(test)]
 fn recursive_operator_test(
-    equation: &Equation,
+    equation: &Equation<i64>,
     adjusting_index: usize,
     operators: &mut Vec<Operator>,
     concat: bool,
 ) -> bool {
     if adjusting_index == operators.len() - 1 {
         operators[adjusting_index] = Operator::Add;
-        if test_equation(equation, &operators) {
+        if test_equation(equation, operators) {
             return true;
         }
         if concat {
             operators[adjusting_index] = Operator::Concatenation;
-            if test_equation(equation, &operators) {
+            if test_equation(equation, operators) {
                 return true;
             }
         }
         operators[adjusting_index] = Operator::Multiply;
-        return test_equation(equation, &operators);
+        test_equation(equation, operators)
     } else {
         operators[adjusting_index] = Operator::Add;
         if recursive_operator_test(equation, adjusting_index + 1, operators, concat) {
@@ -129,52 +498,359 @@ fn recursive_operator_test(
             }
         }
         operators[adjusting_index] = Operator::Multiply;
-        return recursive_operator_test(equation, adjusting_index + 1, operators, concat);
+        recursive_operator_test(equation, adjusting_index + 1, operators, concat)
+    }
+}
+
+// Every value reachable by evaluating the values left to right, along with the operators that reach it.
+// This is the exhaustive fallback for when an operator can't be undone.
+fn forward_operator_search<N: EquationValue, O: EquationOperator<N>>(
+    values: &[N],
+    operators: &[O],
+    overflow_warnings: &mut usize,
+) -> Vec<(N, Solution<O>)> {
+    let mut reachable: Vec<(N, Solution<O>)> = vec![(values[0], vec![])];
+    for value in &values[1..] {
+        let mut next_reachable = vec![];
+        for (running_total, solution) in reachable {
+            for operator in operators {
+                match operator.apply(running_total, *value) {
+                    Ok(next_total) => {
+                        let mut next_solution = solution.clone();
+                        next_solution.push(operator.clone());
+                        next_reachable.push((next_total, next_solution));
+                    }
+                    Err(BranchFailure::Overflow) => *overflow_warnings += 1,
+                    Err(BranchFailure::Undefined) => {}
+                }
+            }
+        }
+        reachable = next_reachable;
+    }
+    reachable
+}
+
+// Depth first take on the forward search for when one solution is enough, stopping at the first operators whose
+// total is accepted rather than holding every reachable total in memory
+fn first_forward_solution<N: EquationValue, O: EquationOperator<N>>(
+    running_total: N,
+    values: &[N],
+    operators: &[O],
+    accept: &dyn Fn(N) -> Result<bool, BranchFailure>,
+    overflow_warnings: &mut usize,
+) -> Option<Solution<O>> {
+    let Some((value, remaining_values)) = values.split_first() else {
+        return match accept(running_total) {
+            Ok(accepted) => accepted.then(Vec::new),
+            Err(BranchFailure::Overflow) => {
+                *overflow_warnings += 1;
+                None
+            }
+            Err(BranchFailure::Undefined) => None,
+        };
+    };
+    for operator in operators {
+        match operator.apply(running_total, *value) {
+            Ok(next_total) => {
+                if let Some(mut solution) = first_forward_solution(
+                    next_total,
+                    remaining_values,
+                    operators,
+                    accept,
+                    overflow_warnings,
+                ) {
+                    solution.insert(0, operator.clone());
+                    return Some(solution);
+                }
+            }
+            Err(BranchFailure::Overflow) => *overflow_warnings += 1,
+            Err(BranchFailure::Undefined) => {}
+        }
+    }
+    None
+}
+
+// Works from the last value back towards the first, undoing each operator against the target.
+// This prunes most of the operator combinations before they're ever evaluated, rather than testing all of them.
+// Solutions come back in left to right order; unless `all_solutions` is set the search stops at the first one found.
+fn reverse_operator_search<N: EquationValue, O: EquationOperator<N>>(
+    target: N,
+    values: &[N],
+    operators: &[O],
+    non_negative: bool,
+    all_solutions: bool,
+    overflow_warnings: &mut usize,
+) -> Vec<Solution<O>> {
+    let Some((&value, remaining_values)) = values.split_last() else {
+        return vec![];
+    };
+    trace!("Target {}, remaining values {:?}", target, values);
+    if remaining_values.is_empty() {
+        return if target == value {
+            vec![vec![]]
+        } else {
+            vec![]
+        };
+    }
+    if non_negative && target < N::zero() {
+        return vec![];
+    }
+
+    let mut solutions = vec![];
+    for operator in operators {
+        let previous_solutions = match operator.undo(target, value) {
+            Ok(Some(candidates)) => {
+                let mut previous_solutions = vec![];
+                for previous_target in candidates {
+                    match operator.apply(previous_target, value) {
+                        Ok(result) if result == target => {}
+                        Err(BranchFailure::Overflow) => {
+                            *overflow_warnings += 1;
+                            continue;
+                        }
+                        _ => continue,
+                    }
+                    previous_solutions.append(&mut reverse_operator_search(
+                        previous_target,
+                        remaining_values,
+                        operators,
+                        non_negative,
+                        all_solutions,
+                        overflow_warnings,
+                    ));
+                }
+                previous_solutions
+            }
+            Ok(None) if !all_solutions => {
+                let reaches_target = |previous_target: N| {
+                    operator
+                        .apply(previous_target, value)
+                        .map(|result| result == target)
+                };
+                first_forward_solution(
+                    remaining_values[0],
+                    &remaining_values[1..],
+                    operators,
+                    &reaches_target,
+                    overflow_warnings,
+                )
+                .into_iter()
+                .collect()
+            }
+            Ok(None) => forward_operator_search(remaining_values, operators, overflow_warnings)
+                .into_iter()
+                .filter(|(previous_target, _solution)| {
+                    match operator.apply(*previous_target, value) {
+                        Ok(result) => result == target,
+                        Err(BranchFailure::Overflow) => {
+                            *overflow_warnings += 1;
+                            false
+                        }
+                        Err(BranchFailure::Undefined) => false,
+                    }
+                })
+                .map(|(_previous_target, solution)| solution)
+                .collect(),
+            Err(BranchFailure::Overflow) => {
+                *overflow_warnings += 1;
+                continue;
+            }
+            Err(BranchFailure::Undefined) => continue,
+        };
+
+        for mut solution in previous_solutions {
+            solution.push(operator.clone());
+            solutions.push(solution);
+            if !all_solutions {
+                return solutions;
+            }
+        }
+    }
+    solutions
+}
+
+fn try_equation_operators<N: EquationValue, O: EquationOperator<N>>(
+    equation: &Equation<N>,
+    operators: &[O],
+    all_solutions: bool,
+) -> EquationSolutions<O> {
+    let non_negative = equation.1.iter().all(|value| value >= &N::zero())
+        && operators
+            .iter()
+            .all(|operator| operator.preserves_non_negative());
+    let mut overflow_warnings = 0;
+    let solutions = reverse_operator_search(
+        equation.0,
+        &equation.1,
+        operators,
+        non_negative,
+        all_solutions,
+        &mut overflow_warnings,
+    );
+    debug_assert!(solutions
+        .iter()
+        .all(|solution| test_equation(equation, solution)));
+    if overflow_warnings > 0 {
+        debug!(
+            "Equation {:?} had {} overflowing branches",
+            equation, overflow_warnings
+        );
+    }
+    EquationSolutions {
+        solutions,
+        overflow_warnings,
     }
 }
 
-fn try_equation_operators(equation: &Equation, concat: bool) -> bool {
-    let mut operators = vec![Operator::Add; equation.1.len() - 1];
-    return recursive_operator_test(equation, 0, &mut operators, concat);
+// Equations are independent, so they're handed out one at a time to each thread as it frees up.
+// That keeps a few pathological equations from holding up a whole chunk, and results are put back into file order afterwards.
+fn solve_equations<N: EquationValue, O: EquationOperator<N>>(
+    file_path: Utf8PathBuf,
+    operators: &[O],
+    all_solutions: bool,
+    threads: usize,
+) -> Result<SolvedEquations<N, O>, Day7Error> {
+    let equations: Vec<Equation<N>> = parse_file(file_path)?;
+    let next_equation = AtomicUsize::new(0);
+    info!(
+        "Solving {} equations across {} threads",
+        equations.len(),
+        threads
+    );
+
+    let mut indexed_solutions: Vec<(usize, EquationSolutions<O>, Duration)> =
+        thread::scope(|scope| {
+            let workers: Vec<_> = (0..threads.max(1))
+                .map(|_| {
+                    scope.spawn(|| {
+                        let mut worker_solutions = vec![];
+                        loop {
+                            let index = next_equation.fetch_add(1, Ordering::Relaxed);
+                            let Some(equation) = equations.get(index) else {
+                                break;
+                            };
+                            let start = Instant::now();
+                            let equation_solutions =
+                                try_equation_operators(equation, operators, all_solutions);
+                            worker_solutions.push((index, equation_solutions, start.elapsed()));
+                        }
+                        worker_solutions
+                    })
+                })
+                .collect();
+            workers
+                .into_iter()
+                .flat_map(|worker| worker.join().expect("equation solver thread to not panic"))
+                .collect()
+        });
+    indexed_solutions.sort_by_key(|(index, _equation_solutions, _elapsed)| *index);
+
+    Ok(equations
+        .into_iter()
+        .zip(indexed_solutions)
+        .map(|(equation, (_index, equation_solutions, elapsed))| {
+            (equation, equation_solutions, elapsed)
+        })
+        .collect())
 }
 
-fn calculate_total_equations_result(file_path: Utf8PathBuf, concat: bool) -> i64 {
-    let equations: Vec<Equation> = parse_file(file_path);
-    let mut total = 0;
+fn log_slowest_equations<N: EquationValue, O>(
+    solved_equations: &SolvedEquations<N, O>,
+    count: usize,
+) {
+    for (line, (equation, _equation_solutions, elapsed)) in solved_equations.iter().enumerate() {
+        debug!("Line {} {:?} took {:.2?}", line + 1, equation, elapsed);
+    }
+
+    let mut timings: Vec<(usize, &Equation<N>, &Duration)> = solved_equations
+        .iter()
+        .enumerate()
+        .map(|(line, (equation, _equation_solutions, elapsed))| (line + 1, equation, elapsed))
+        .collect();
+    timings.sort_by(|a, b| b.2.cmp(a.2));
+    for (line, equation, elapsed) in timings.into_iter().take(count) {
+        info!(
+            "Slow equation on line {} with {} values took {:.2?}: {:?}",
+            line,
+            equation.1.len(),
+            elapsed,
+            equation
+        );
+    }
+}
 
-    for equation in equations {
-        if try_equation_operators(&equation, concat) {
-            total += equation.0;
+fn total_solved_equations<N: EquationValue, O>(
+    solved_equations: &SolvedEquations<N, O>,
+) -> Result<EquationsResult<N>, Day7Error> {
+    let mut result = EquationsResult {
+        total: N::zero(),
+        overflow_warnings: 0,
+    };
+    for (equation, equation_solutions, _elapsed) in solved_equations {
+        result.overflow_warnings += equation_solutions.overflow_warnings;
+        if !equation_solutions.solutions.is_empty() {
+            result.total = result
+                .total
+                .checked_add(&equation.0)
+                .ok_or(Day7Error::TotalOverflow)?;
         }
     }
-    total
+    Ok(result)
 }
 
-// #[derive(Debug, PartialEq)]
-// enum Day7Error {
-//     Unsolveable,
-// }
+fn calculate_total_equations_result<N: EquationValue, O: EquationOperator<N>>(
+    file_path: Utf8PathBuf,
+    operators: &[O],
+    threads: usize,
+    slowest: usize,
+) -> Result<EquationsResult<N>, Day7Error> {
+    let solved_equations = solve_equations(file_path, operators, false, threads)?;
+    log_slowest_equations(&solved_equations, slowest);
+    total_solved_equations(&solved_equations)
+}
 
-// impl fmt::Display for Day7Error {
-//     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-//         match *self {
-//             Day7Error::Unsolveable => {
-//                 write!(f, "equation is unsolveable")
-//             }
-//         }
-//     }
-// }
+#[derive(Debug, PartialEq)]
+pub enum Day7Error {
+    UnknownOperator(String),
+    InvalidValue(String),
+    ValueOverflow(String),
+    TotalOverflow,
+}
 
-// impl error::Error for Day7Error {
-//     fn source(&self) -> Option<&(dyn error::Error + 'static)> {
-//         match *self {
-//             Day7Error::Unsolveable => None,
-//         }
-//     }
-// }
+impl fmt::Display for Day7Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Day7Error::UnknownOperator(operator) => {
+                write!(f, "unknown operator '{}'", operator)
+            }
+            Day7Error::InvalidValue(value) => {
+                write!(f, "'{}' is not a valid number", value)
+            }
+            Day7Error::ValueOverflow(value) => {
+                write!(f, "'{}' is too large, try --wide", value)
+            }
+            Day7Error::TotalOverflow => {
+                write!(f, "total of valid equations overflowed, try --wide")
+            }
+        }
+    }
+}
+
+impl error::Error for Day7Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match *self {
+            Day7Error::UnknownOperator(_) => None,
+            Day7Error::InvalidValue(_) => None,
+            Day7Error::ValueOverflow(_) => None,
+            Day7Error::TotalOverflow => None,
+        }
+    }
+}
 
 #[cfg(test)]
 mod tests {
+    use proptest::prelude::*;
+
     use super::*;
     use crate::test_init;
 
@@ -182,7 +858,7 @@ mod tests {
     fn test_file_input() {
         test_init();
         assert_eq!(
-            vec![
+            Ok(vec![
                 (190, vec![10, 19]),
                 (3267, vec![81, 40, 27]),
                 (83, vec![17, 5]),
@@ -192,8 +868,8 @@ mod tests {
                 (192, vec![17, 8, 14]),
                 (21037, vec![9, 7, 18, 13]),
                 (292, vec![11, 6, 16, 20])
-            ],
-            parse_file(Utf8PathBuf::from("./src/puzzle_inputs/day7_sample.txt"))
+            ]),
+            parse_file::<i64>(Utf8PathBuf::from("./src/puzzle_inputs/day7_sample.txt"))
         )
     }
 
@@ -202,22 +878,132 @@ mod tests {
         test_init();
         assert!(test_equation(
             &(3267, vec![81, 40, 27]),
-            &vec![Operator::Add, Operator::Multiply]
+            &[Operator::Add, Operator::Multiply]
         ))
     }
 
     #[test]
     fn test_equation_operators() {
         test_init();
-        assert!(try_equation_operators(&(292, vec![11, 6, 16, 20]), false))
+        assert_eq!(
+            vec![vec![Operator::Add, Operator::Multiply, Operator::Add]],
+            try_equation_operators(
+                &(292, vec![11, 6, 16, 20]),
+                &default_operators(false),
+                false
+            )
+            .solutions
+        )
+    }
+
+    #[test]
+    fn test_equation_all_solutions() {
+        test_init();
+        assert_eq!(
+            vec![
+                vec![Operator::Multiply, Operator::Add],
+                vec![Operator::Add, Operator::Multiply]
+            ],
+            try_equation_operators(&(3267, vec![81, 40, 27]), &default_operators(false), true)
+                .solutions
+        )
+    }
+
+    #[test]
+    fn test_display_solved_equation() {
+        test_init();
+        assert_eq!(
+            "3267: 81 + 40 * 27",
+            SolvedEquation {
+                equation: &(3267, vec![81, 40, 27]),
+                operators: &[Operator::Add, Operator::Multiply]
+            }
+            .to_string()
+        );
+        assert_eq!(
+            "156: 15 || 6",
+            SolvedEquation {
+                equation: &(156, vec![15, 6]),
+                operators: &[Operator::Concatenation]
+            }
+            .to_string()
+        );
     }
 
     #[test]
     fn test_unsolveable_equation() {
         test_init();
         assert_eq!(
-            false,
-            try_equation_operators(&(161011, vec![16, 10, 13]), false)
+            EquationSolutions {
+                solutions: vec![],
+                overflow_warnings: 0
+            },
+            try_equation_operators(
+                &(161011, vec![16, 10, 13]),
+                &default_operators(false),
+                false
+            )
+        )
+    }
+
+    #[test]
+    fn test_numeric_concatenation() {
+        test_init();
+        assert_eq!(Ok(156), concatenate(15, 6));
+        assert_eq!(Ok(1510), concatenate(15, 10));
+        assert_eq!(Ok(150), concatenate(15, 0));
+        assert_eq!(Ok(-53), concatenate(-5, 3));
+        assert_eq!(Err(BranchFailure::Undefined), concatenate(5, -3));
+        assert_eq!(Err(BranchFailure::Overflow), concatenate(i64::MAX, 5));
+    }
+
+    #[test]
+    fn test_reverse_search_matches_brute_force() {
+        test_init();
+        for concat in [false, true] {
+            for equation in
+                parse_file::<i64>(Utf8PathBuf::from("./src/puzzle_inputs/day7_sample.txt"))
+                    .expect("sample to parse")
+            {
+                let mut operators = vec![Operator::Add; equation.1.len() - 1];
+                assert_eq!(
+                    recursive_operator_test(&equation, 0, &mut operators, concat),
+                    !try_equation_operators(&equation, &default_operators(concat), false)
+                        .solutions
+                        .is_empty(),
+                    "Equation {:?}, concatenation {}",
+                    equation,
+                    concat
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_multiply_by_zero_equation() {
+        test_init();
+        assert_eq!(
+            vec![vec![Operator::Add, Operator::Multiply]],
+            try_equation_operators(&(0, vec![5, 3, 0]), &default_operators(false), false).solutions
+        );
+        assert_eq!(
+            2,
+            try_equation_operators(&(0, vec![5, 3, 0]), &default_operators(false), true)
+                .solutions
+                .len()
+        );
+    }
+
+    #[test]
+    fn test_long_equation_with_concatenation() {
+        test_init();
+        // 1 || 2 || ... || 9 + 1 + ... + 1, with 20 operands this would be 3^19 combinations to brute force
+        let mut values: Vec<i64> = (1..=9).collect();
+        values.extend(vec![1; 11]);
+        assert!(
+            !try_equation_operators(&(123456789 + 11, values), &default_operators(true), false)
+                .solutions
+                .is_empty()
         )
     }
 
@@ -225,10 +1011,15 @@ mod tests {
     fn test_calculate_total_equations_from_sample() {
         test_init();
         assert_eq!(
-            3749,
-            calculate_total_equations_result(
+            Ok(EquationsResult {
+                total: 3749,
+                overflow_warnings: 0
+            }),
+            calculate_total_equations_result::<i64, _>(
                 Utf8PathBuf::from("./src/puzzle_inputs/day7_sample.txt"),
-                false
+                &default_operators(false),
+                1,
+                0
             )
         )
     }
@@ -237,11 +1028,462 @@ mod tests {
     fn test_calculate_total_equations_from_sample_with_concatenation() {
         test_init();
         assert_eq!(
-            11387,
-            calculate_total_equations_result(
+            Ok(EquationsResult {
+                total: 11387,
+                overflow_warnings: 0
+            }),
+            calculate_total_equations_result::<i64, _>(
                 Utf8PathBuf::from("./src/puzzle_inputs/day7_sample.txt"),
-                true
+                &default_operators(true),
+                1,
+                0
+            )
+        )
+    }
+
+    #[test]
+    fn test_calculate_total_equations_from_sample_wide() {
+        test_init();
+        assert_eq!(
+            Ok(EquationsResult {
+                total: 11387,
+                overflow_warnings: 0
+            }),
+            calculate_total_equations_result::<i128, _>(
+                Utf8PathBuf::from("./src/puzzle_inputs/day7_sample.txt"),
+                &default_operators(true),
+                1,
+                0
             )
         )
     }
+
+    #[test]
+    fn test_solve_equations_from_sample() {
+        test_init();
+        let solved_equations = solve_equations::<i64, _>(
+            Utf8PathBuf::from("./src/puzzle_inputs/day7_sample.txt"),
+            &default_operators(true),
+            false,
+            4,
+        )
+        .expect("sample to parse");
+        assert_eq!(9, solved_equations.len());
+        assert_eq!(
+            6,
+            solved_equations
+                .iter()
+                .filter(
+                    |(_equation, equation_solutions, _elapsed)| !equation_solutions
+                        .solutions
+                        .is_empty()
+                )
+                .count()
+        );
+    }
+
+    #[derive(Clone, Debug)]
+    struct Maximum;
+
+    impl Display for Maximum {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(f, "max")
+        }
+    }
+
+    impl<N: EquationValue> EquationOperator<N> for Maximum {
+        fn apply(&self, left: N, right: N) -> Result<N, BranchFailure> {
+            Ok(left.max(right))
+        }
+    }
+
+    #[test]
+    fn test_parse_operators() {
+        test_init();
+        assert_eq!(
+            Ok(vec![
+                Operator::Add,
+                Operator::Multiply,
+                Operator::Concatenation,
+                Operator::Subtract,
+                Operator::Divide,
+                Operator::Exponent
+            ]),
+            "+,*,||,-,/,^"
+                .split(",")
+                .map(Operator::from_str)
+                .collect::<Result<Vec<Operator>, Day7Error>>()
+        );
+        assert_eq!(
+            Err(Day7Error::UnknownOperator(String::from("%"))),
+            Operator::from_str("%")
+        );
+    }
+
+    #[test]
+    fn test_subtraction_and_division_equation() {
+        test_init();
+        assert_eq!(
+            vec![vec![Operator::Divide, Operator::Subtract]],
+            try_equation_operators(
+                &(2, vec![10, 2, 3]),
+                &[Operator::Subtract, Operator::Divide],
+                true
+            )
+            .solutions
+        );
+    }
+
+    #[test]
+    fn test_inexact_division_fails() {
+        test_init();
+        assert!(
+            try_equation_operators(&(3, vec![10, 3]), &[Operator::Divide], true)
+                .solutions
+                .is_empty()
+        );
+        assert!(
+            try_equation_operators(&(3, vec![10, 0]), &[Operator::Divide], true)
+                .solutions
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_exponent_equation() {
+        test_init();
+        assert_eq!(
+            vec![vec![Operator::Exponent, Operator::Exponent]],
+            try_equation_operators(&(81, vec![3, 2, 2]), &[Operator::Exponent], true).solutions
+        );
+    }
+
+    #[test]
+    fn test_wide_exponent_finds_exact_root() {
+        test_init();
+        let root: i128 = 4611686018427400249;
+        let equation: Equation<i128> = (root * root, vec![root, 2]);
+        assert_eq!(
+            vec![vec![Operator::Exponent]],
+            try_equation_operators(&equation, &[Operator::Exponent], true).solutions
+        );
+        assert_eq!(
+            vec![vec![Operator::Exponent]],
+            try_equation_operators(&equation, &[Operator::Multiply, Operator::Exponent], true)
+                .solutions
+        );
+        assert_eq!(root, integer_root(root * root, 2));
+        assert_eq!(root - 1, integer_root(root * root - 1, 2));
+        assert_eq!(3, integer_root(-27i64, 3));
+        assert_eq!(1, integer_root(i128::MIN, 127));
+    }
+
+    #[test]
+    fn test_exponents_beyond_u32() {
+        test_init();
+        let exponent: i64 = 5_000_000_000;
+        assert_eq!(
+            vec![vec![Operator::Exponent]],
+            try_equation_operators(&(1, vec![1, exponent]), &[Operator::Exponent], true).solutions
+        );
+        assert_eq!(
+            vec![vec![Operator::Exponent]],
+            try_equation_operators(&(0, vec![0, exponent]), &[Operator::Exponent], true).solutions
+        );
+        assert_eq!(
+            vec![vec![Operator::Add, Operator::Exponent]],
+            try_equation_operators(
+                &(1, vec![-2, 1, exponent]),
+                &[Operator::Add, Operator::Exponent],
+                true
+            )
+            .solutions
+        );
+        assert!(
+            try_equation_operators(&(2, vec![2, exponent]), &[Operator::Exponent], true)
+                .solutions
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_overflowing_equation_is_pruned_with_warning() {
+        test_init();
+        let equation_solutions =
+            try_equation_operators(&(0, vec![i64::MAX, 2, 0]), &default_operators(true), true);
+        assert!(equation_solutions.solutions.is_empty());
+        assert!(equation_solutions.overflow_warnings > 0);
+    }
+
+    #[test]
+    fn test_wide_equation_solves_past_64_bits() {
+        test_init();
+        let total = "18446744073709551614";
+        assert_eq!(
+            Err(Day7Error::ValueOverflow(String::from(total))),
+            parse_value::<i64>(total)
+        );
+        assert_eq!(
+            vec![vec![Operator::Multiply]],
+            try_equation_operators(
+                &(
+                    parse_value::<i128>(total).expect("to fit in 128 bits"),
+                    vec![i64::MAX as i128, 2]
+                ),
+                &default_operators(false),
+                false
+            )
+            .solutions
+        );
+    }
+
+    #[test]
+    fn test_total_overflow_is_an_error() {
+        test_init();
+        let solved = EquationSolutions {
+            solutions: vec![vec![Operator::Add]],
+            overflow_warnings: 0,
+        };
+        assert_eq!(
+            Err(Day7Error::TotalOverflow),
+            total_solved_equations(&vec![
+                (
+                    (i64::MAX, vec![i64::MAX - 1, 1]),
+                    solved.clone(),
+                    Duration::ZERO
+                ),
+                ((i64::MAX, vec![i64::MAX - 1, 1]), solved, Duration::ZERO)
+            ])
+        );
+    }
+
+    #[test]
+    fn test_custom_operator() {
+        test_init();
+        assert_eq!(
+            "7: 3 max 7 max 5",
+            SolvedEquation {
+                equation: &(7, vec![3, 7, 5]),
+                operators: &try_equation_operators(&(7, vec![3, 7, 5]), &[Maximum], false)
+                    .solutions[0]
+            }
+            .to_string()
+        );
+    }
+
+    #[test]
+    fn test_reverse_search_matches_forward_search_for_all_operators() {
+        test_init();
+        let operators = [
+            Operator::Add,
+            Operator::Multiply,
+            Operator::Concatenation,
+            Operator::Subtract,
+            Operator::Divide,
+            Operator::Exponent,
+        ];
+        for values in [
+            vec![2, 3, 4],
+            vec![0, 5, 0],
+            vec![10, 2, 5, 1],
+            vec![3, 0, 3, 1],
+            vec![-4, 2, 2],
+        ] {
+            let reachable = forward_operator_search::<i64, _>(&values, &operators, &mut 0);
+            for (target, _solution) in &reachable {
+                let equation = (*target, values.clone());
+                let mut expected: Vec<String> = reachable
+                    .iter()
+                    .filter(|(total, _solution)| total == target)
+                    .map(|(_total, solution)| {
+                        SolvedEquation {
+                            equation: &equation,
+                            operators: solution,
+                        }
+                        .to_string()
+                    })
+                    .collect();
+                let mut found: Vec<String> = try_equation_operators(&equation, &operators, true)
+                    .solutions
+                    .iter()
+                    .map(|solution| {
+                        SolvedEquation {
+                            equation: &equation,
+                            operators: solution,
+                        }
+                        .to_string()
+                    })
+                    .collect();
+                expected.sort();
+                found.sort();
+                assert_eq!(expected, found, "Equation {:?}", equation);
+            }
+        }
+    }
+
+    #[test]
+    fn test_solve_equations_threads_keep_file_order() {
+        test_init();
+        let path = Utf8PathBuf::from("./src/puzzle_inputs/day7_sample.txt");
+        let single_threaded =
+            solve_equations::<i64, _>(path.clone(), &default_operators(true), true, 1)
+                .expect("sample to parse");
+        for threads in [2, 3, 16] {
+            let multi_threaded =
+                solve_equations::<i64, _>(path.clone(), &default_operators(true), true, threads)
+                    .expect("sample to parse");
+            assert_eq!(single_threaded.len(), multi_threaded.len());
+            for (single, multi) in single_threaded.iter().zip(&multi_threaded) {
+                assert_eq!((&single.0, &single.1), (&multi.0, &multi.1));
+            }
+            assert_eq!(
+                total_solved_equations(&single_threaded),
+                total_solved_equations(&multi_threaded)
+            );
+        }
+    }
+
+    #[test]
+    fn test_long_undo_fallbacks_stop_at_first_solution() {
+        test_init();
+        // Neither `* 0` nor `^ 0` can be undone, and walking every operator combination of 40 values would never finish
+        let equations: [(Equation<i64>, Vec<Operator>); 2] = [
+            ((0, (1..=40).chain([0]).collect()), default_operators(true)),
+            (
+                (1, (1..=40).chain([0]).collect()),
+                vec![Operator::Add, Operator::Concatenation, Operator::Exponent],
+            ),
+        ];
+        for (equation, operators) in equations {
+            let solutions = try_equation_operators(&equation, &operators, false);
+            assert_eq!(1, solutions.solutions.len(), "Equation {:?}", equation);
+            assert!(test_equation(&equation, &solutions.solutions[0]));
+        }
+    }
+
+    const ALL_OPERATORS: [Operator; 6] = [
+        Operator::Add,
+        Operator::Multiply,
+        Operator::Concatenation,
+        Operator::Subtract,
+        Operator::Divide,
+        Operator::Exponent,
+    ];
+
+    // Applies an operator in 128 bits, then fails anything that doesn't fit back into 64 bits
+    fn oracle_apply(operator: &Operator, left: i64, right: i64) -> Option<i64> {
+        let (left, right): (i128, i128) = (left.into(), right.into());
+        let result = match operator {
+            Operator::Add => left.checked_add(right),
+            Operator::Multiply => left.checked_mul(right),
+            Operator::Concatenation if right >= 0 => format!("{}{}", left, right).parse().ok(),
+            Operator::Concatenation => None,
+            Operator::Subtract => left.checked_sub(right),
+            Operator::Divide if right != 0 && left % right == 0 => Some(left / right),
+            Operator::Divide => None,
+            Operator::Exponent => u32::try_from(right)
+                .ok()
+                .and_then(|exponent| left.checked_pow(exponent)),
+        }?;
+        i64::try_from(result).ok()
+    }
+
+    // Every combination of operators, evaluated left to right
+    fn oracle_solutions(
+        (total, values): &Equation<i64>,
+        operators: &[Operator],
+    ) -> Vec<Solution<Operator>> {
+        let mut combinations: Vec<Solution<Operator>> = vec![vec![]];
+        for _ in 1..values.len() {
+            combinations = combinations
+                .into_iter()
+                .flat_map(|combination| {
+                    operators.iter().map(move |operator| {
+                        let mut next = combination.clone();
+                        next.push(operator.clone());
+                        next
+                    })
+                })
+                .collect();
+        }
+        combinations
+            .into_iter()
+            .filter(|combination| {
+                let mut running_total = Some(values[0]);
+                for (value, operator) in values[1..].iter().zip(combination) {
+                    running_total =
+                        running_total.and_then(|left| oracle_apply(operator, left, *value));
+                }
+                running_total == Some(*total)
+            })
+            .collect()
+    }
+
+    fn solution_strings(equation: &Equation<i64>, solutions: &[Solution<Operator>]) -> Vec<String> {
+        let mut strings: Vec<String> = solutions
+            .iter()
+            .map(|solution| {
+                SolvedEquation {
+                    equation,
+                    operators: solution,
+                }
+                .to_string()
+            })
+            .collect();
+        strings.sort();
+        strings
+    }
+
+    // Half the targets come from evaluating a random operator combination, so plenty of equations are solvable
+    fn equation_strategy(
+        values: impl Strategy<Value = i64> + Clone,
+        lengths: std::ops::Range<usize>,
+        operators: &'static [Operator],
+    ) -> impl Strategy<Value = Equation<i64>> {
+        proptest::collection::vec(values, lengths).prop_flat_map(move |values| {
+            let combination =
+                proptest::collection::vec(proptest::sample::select(operators), values.len() - 1);
+            (Just(values), combination, -50i64..5000, any::<bool>()).prop_map(
+                |(values, combination, random_target, use_combination)| {
+                    let mut running_total = Some(values[0]);
+                    for (value, operator) in values[1..].iter().zip(&combination) {
+                        running_total =
+                            running_total.and_then(|left| oracle_apply(operator, left, *value));
+                    }
+                    let target = match running_total {
+                        Some(total) if use_combination => total,
+                        _ => random_target,
+                    };
+                    (target, values)
+                },
+            )
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn prop_reverse_search_matches_recursive_brute_force(
+            equation in equation_strategy(1i64..30, 2..7, &ALL_OPERATORS[..3]),
+            concat: bool
+        ) {
+            let mut operators = vec![Operator::Add; equation.1.len() - 1];
+            prop_assert_eq!(
+                recursive_operator_test(&equation, 0, &mut operators, concat),
+                !try_equation_operators(&equation, &default_operators(concat), false)
+                    .solutions
+                    .is_empty()
+            );
+        }
+
+        #[test]
+        fn prop_all_solutions_match_oracle(
+            equation in equation_strategy(-3i64..12, 1..5, &ALL_OPERATORS)
+        ) {
+            let found = try_equation_operators(&equation, &ALL_OPERATORS, true).solutions;
+            prop_assert_eq!(
+                solution_strings(&equation, &oracle_solutions(&equation, &ALL_OPERATORS)),
+                solution_strings(&equation, &found)
+            );
+        }
+    }
 }
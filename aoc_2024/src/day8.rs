@@ -1,4 +1,6 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::{error, fmt};
 
 use camino::Utf8PathBuf;
 use clap::Subcommand;
@@ -12,39 +14,159 @@ pub enum Day8Commands {
         /// Input File Path
         #[arg(short, long)]
         path: Utf8PathBuf,
-        /// Whether to account for resonant harmonics
+        /// Multiples of the antenna delta to place antinodes at, e.g. "1..=1", "0.." or "2..=5".
+        /// Without a value, every multiple along the whole line counts
+        #[arg(
+            long,
+            default_value_t = HarmonicRange::DOUBLE_DISTANCE,
+            default_missing_value = "..",
+            num_args = 0..=1
+        )]
+        harmonics: HarmonicRange,
+        /// Whether to count harmonics in steps of the delta reduced by its gcd, so with "--harmonics" every point in
+        /// line with two antennas counts
         #[arg(long, default_value_t = false)]
-        harmonics: bool,
+        lattice: bool,
+    },
+    /// Renders antennas and antinodes onto the map, with a per-frequency breakdown
+    Render {
+        /// Input File Path
+        #[arg(short, long)]
+        path: Utf8PathBuf,
+        /// Multiples of the antenna delta to place antinodes at, e.g. "1..=1", "0.." or "2..=5".
+        /// Without a value, every multiple along the whole line counts
+        #[arg(
+            long,
+            default_value_t = HarmonicRange::DOUBLE_DISTANCE,
+            default_missing_value = "..",
+            num_args = 0..=1
+        )]
+        harmonics: HarmonicRange,
+        /// Whether to count harmonics in steps of the delta reduced by its gcd, so with "--harmonics" every point in
+        /// line with two antennas counts
+        #[arg(long, default_value_t = false)]
+        lattice: bool,
+        /// Whether to color the map with ANSI escape codes, one color per frequency
+        #[arg(long, default_value_t = false)]
+        color: bool,
     },
 }
 
 pub fn day8_cli_command_processing(command: &Day8Commands) {
     match command {
-        Day8Commands::GenerateAntinodes { path, harmonics } => {
+        Day8Commands::GenerateAntinodes {
+            path,
+            harmonics,
+            lattice,
+        } => {
             info!("Command received to generate and total antinodes");
             println!(
                 "Total unique antinodes: {}",
-                calculate_all_antinodes(path.clone(), harmonics.to_owned())
+                calculate_all_antinodes(path.clone(), &harmonics.with_lattice(*lattice))
+            );
+        }
+        Day8Commands::Render {
+            path,
+            harmonics,
+            lattice,
+            color,
+        } => {
+            info!("Command received to render antinodes");
+            let (rendered_map, breakdown, total) = render_antinodes(
+                path.clone(),
+                &harmonics.with_lattice(*lattice),
+                color.to_owned(),
             );
+            println!("{}", rendered_map);
+            println!("{}", format_breakdown_table(&breakdown));
+            println!("Total unique antinodes: {}", total);
         }
     }
 }
-#[derive(Debug, PartialEq, Eq, Hash, Clone)]
-struct Position {
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+struct Point {
     x: i32,
     y: i32,
-    frequency: char,
 }
 
-#[derive(Debug, PartialEq, Eq, Hash, Clone)]
-struct Coordinates {
-    x: i32,
-    y: i32,
+#[derive(Debug, PartialEq)]
+struct AntennaMap {
+    antennas: HashMap<char, Vec<Point>>,
+    width: usize,
+    height: usize,
+}
+
+impl AntennaMap {
+    // Frequencies in a stable order, so output doesn't shuffle between runs
+    fn sorted_frequencies(&self) -> Vec<char> {
+        let mut frequencies: Vec<char> = self.antennas.keys().copied().collect();
+        frequencies.sort();
+        frequencies
+    }
+}
+
+// One bit per grid cell, so collecting antinodes doesn't need hashing or a separate dedup pass
+#[derive(Debug, PartialEq, Clone)]
+struct AntinodeGrid {
+    bits: Vec<u64>,
+    width: usize,
+    height: usize,
+}
+
+impl AntinodeGrid {
+    fn new(width: usize, height: usize) -> Self {
+        AntinodeGrid {
+            bits: vec![0; (width * height).div_ceil(64)],
+            width,
+            height,
+        }
+    }
+
+    fn index(&self, point: &Point) -> Option<usize> {
+        if is_in_bounds(point.x, point.y, self.width as i32, self.height as i32) {
+            Some(point.y as usize * self.width + point.x as usize)
+        } else {
+            None
+        }
+    }
+
+    // Points off the map are ignored
+    fn insert(&mut self, point: &Point) {
+        if let Some(index) = self.index(point) {
+            self.bits[index / 64] |= 1 << (index % 64);
+        }
+    }
+
+    fn contains(&self, point: &Point) -> bool {
+        self.index(point)
+            .is_some_and(|index| self.bits[index / 64] & (1 << (index % 64)) != 0)
+    }
+
+    fn len(&self) -> usize {
+        self.bits
+            .iter()
+            .map(|word| word.count_ones() as usize)
+            .sum()
+    }
+
+    fn union_with(&mut self, other: &AntinodeGrid) {
+        for (word, other_word) in self.bits.iter_mut().zip(&other.bits) {
+            *word |= other_word;
+        }
+    }
+
+    fn intersection_len(&self, other: &AntinodeGrid) -> usize {
+        self.bits
+            .iter()
+            .zip(&other.bits)
+            .map(|(word, other_word)| (word & other_word).count_ones() as usize)
+            .sum()
+    }
 }
 
-fn parse_file(file_path: Utf8PathBuf) -> (Vec<HashSet<Position>>, usize, usize) {
-    let mut map: Vec<HashSet<Position>> = vec![];
-    let mut frequency_index: HashMap<char, usize> = HashMap::new();
+fn parse_file(file_path: Utf8PathBuf) -> AntennaMap {
+    let mut antennas: HashMap<char, Vec<Point>> = HashMap::new();
 
     let content = read_file(file_path);
     let string_map: Vec<&str> = content.split("\n").collect();
@@ -53,243 +175,385 @@ fn parse_file(file_path: Utf8PathBuf) -> (Vec<HashSet<Position>>, usize, usize)
     for (y, row) in string_map.iter().enumerate() {
         for (x, char) in row.chars().enumerate() {
             if char != '.' {
-                let antenna = Position {
+                antennas.entry(char).or_default().push(Point {
                     x: x as i32,
                     y: y as i32,
-                    frequency: char,
-                };
-
-                let index = frequency_index.get(&char);
-                if index.is_some() {
-                    map[index.expect("expect to be valid index").to_owned()].insert(antenna);
-                } else {
-                    let mut hash_set: HashSet<Position> = HashSet::new();
-                    hash_set.insert(antenna);
-                    map.push(hash_set);
-                    frequency_index.insert(char, map.len() - 1);
-                }
+                });
             }
         }
     }
 
-    (map, width, height)
+    AntennaMap {
+        antennas,
+        width,
+        height,
+    }
 }
 
-fn calculate_limitless_antinodes(
-    antenna1: &Position,
-    antenna2: &Position,
-    map_width: i32,
-    map_height: i32,
-) -> Vec<Position> {
-    let width = (antenna1.x - antenna2.x).abs();
-    let height = (antenna1.y - antenna2.y).abs();
-
-    debug!("Calculated Width: {} Calculated Height: {}", width, height);
-    let mut antinodes = vec![];
-
-    // top left
-    let mut x = antenna1.x;
-    let mut y = antenna1.y;
-    debug!("(x,y) ({},{})", x, y);
-    while x >= 0 && y >= 0 && antenna1.y <= antenna2.y && antenna1.x <= antenna2.x {
-        debug!("Top Left Antinode");
-        antinodes.push(Position {
-            x,
-            y,
-            frequency: antenna1.frequency,
-        });
-        x -= width;
-        y -= height;
-    }
-
-    debug!("(x,y) ({},{})", x, y);
-    // top right
-    x = antenna1.x;
-    y = antenna1.y;
-
-    debug!("(x,y) ({},{})", x, y);
-    while x < map_width && y >= 0 && antenna1.y < antenna2.y && antenna1.x > antenna2.x {
-        debug!("Top Right Antinode");
-        antinodes.push(Position {
-            x,
-            y,
-            frequency: antenna1.frequency,
-        });
-        x += width;
-        y -= height;
-    }
-
-    // bottom left
-    x = antenna1.x;
-    y = antenna1.y;
-    while x >= 0 && y < map_height && antenna1.y > antenna2.y && antenna1.x < antenna2.x {
-        debug!("Bottom Left Antinode");
-        antinodes.push(Position {
-            x,
-            y,
-            frequency: antenna1.frequency,
-        });
-        x -= width;
-        y += height;
-    }
-
-    // bottom right
-    x = antenna1.x;
-    y = antenna1.y;
-    while x < map_width && y < map_height && antenna1.y > antenna2.y && antenna1.x > antenna2.x {
-        debug!("Bottom Right Antinode");
-        antinodes.push(Position {
-            x,
-            y,
-            frequency: antenna1.frequency,
-        });
-        x += width;
-        y += height;
+fn gcd(a: i32, b: i32) -> i32 {
+    if b == 0 {
+        a.abs()
+    } else {
+        gcd(b, a % b)
     }
-    debug!("Antinodes: {:?}", antinodes);
-    antinodes
 }
 
-fn calculate_antinodes(antenna1: Position, antenna2: Position) -> (Position, Position) {
-    let width = (antenna1.x - antenna2.x).abs();
-    let height = (antenna1.y - antenna2.y).abs();
-
-    debug!("Calculated Width: {} Calculated Height: {}", width, height);
-    let antinode1: Position;
-    let antinode2: Position;
-    // antenna1 is top left or they are in a line
-    if antenna1.y <= antenna2.y && antenna1.x <= antenna2.x {
-        trace!("Antenna1 is top left");
-        antinode1 = Position {
-            x: antenna1.x - width,
-            y: antenna1.y - height,
-            frequency: antenna1.frequency,
-        };
-        antinode2 = Position {
-            x: antenna2.x + width,
-            y: antenna2.y + height,
-            frequency: antenna1.frequency,
-        };
+fn is_in_bounds(x: i32, y: i32, map_width: i32, map_height: i32) -> bool {
+    x >= 0 && x < map_width && y >= 0 && y < map_height
+}
+
+// Which multiples of the antenna-to-antenna delta count as antinodes, measured outward from each antenna.
+// `1..=1` is the original double distance rule. With `lattice` the multiples are of the delta reduced by its gcd
+// instead, so `..` covers every point on the whole line through both antennas.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct HarmonicRange {
+    start: Option<i64>,
+    end: Option<i64>,
+    lattice: bool,
+}
+
+impl HarmonicRange {
+    const DOUBLE_DISTANCE: HarmonicRange = HarmonicRange {
+        start: Some(1),
+        end: Some(1),
+        lattice: false,
+    };
+    #[cfg(test)]
+    const WHOLE_LINE: HarmonicRange = HarmonicRange {
+        start: None,
+        end: None,
+        lattice: true,
+    };
+
+    fn with_lattice(self, lattice: bool) -> HarmonicRange {
+        HarmonicRange { lattice, ..self }
     }
-    // antenna1 is top right
-    else if antenna1.y < antenna2.y && antenna1.x > antenna2.x {
-        trace!("Antenna1 is top right");
-        antinode1 = Position {
-            x: antenna1.x + width,
-            y: antenna1.y - height,
-            frequency: antenna1.frequency,
-        };
-        antinode2 = Position {
-            x: antenna2.x - width,
-            y: antenna2.y + height,
-            frequency: antenna1.frequency,
-        };
+}
+
+impl fmt::Display for HarmonicRange {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let bound = |bound: Option<i64>| bound.map_or(String::new(), |bound| bound.to_string());
+        match self.end {
+            Some(_) => write!(f, "{}..={}", bound(self.start), bound(self.end)),
+            None => write!(f, "{}..", bound(self.start)),
+        }
     }
-    // antenna1 is bottom left
-    else if antenna1.y > antenna2.y && antenna1.x < antenna2.x {
-        trace!("Antenna1 is bottom left");
-        antinode1 = Position {
-            x: antenna1.x - width,
-            y: antenna1.y + height,
-            frequency: antenna1.frequency,
-        };
-        antinode2 = Position {
-            x: antenna2.x + width,
-            y: antenna2.y - height,
-            frequency: antenna1.frequency,
+}
+
+impl FromStr for HarmonicRange {
+    type Err = Day8Error;
+
+    fn from_str(range: &str) -> Result<Self, Self::Err> {
+        let invalid = || Day8Error::InvalidHarmonicRange(range.to_owned());
+        let parse_bound = |bound: &str| -> Result<Option<i64>, Day8Error> {
+            if bound.is_empty() {
+                Ok(None)
+            } else {
+                bound.parse().map(Some).map_err(|_| invalid())
+            }
         };
-    }
-    // antenna1 is bottom right
-    // if antenna1.y > antenna2.y && antenna1.x > antenna2.x {
-    else {
-        trace!("Antenna1 is bottom right");
-        antinode1 = Position {
-            x: antenna1.x + width,
-            y: antenna1.y + height,
-            frequency: antenna1.frequency,
+
+        let (start, end) = if let Some((start, end)) = range.trim().split_once("..=") {
+            if end.is_empty() {
+                return Err(invalid());
+            }
+            (start, end)
+        } else if let Some((start, end)) = range.trim().split_once("..") {
+            // An exclusive end is easy to misread against the whole line rule, so only `..=` is accepted
+            if !end.is_empty() {
+                return Err(invalid());
+            }
+            (start, end)
+        } else {
+            return Err(invalid());
         };
-        antinode2 = Position {
-            x: antenna2.x - width,
-            y: antenna2.y - height,
-            frequency: antenna1.frequency,
+
+        let harmonic_range = HarmonicRange {
+            start: parse_bound(start)?,
+            end: parse_bound(end)?,
+            lattice: false,
         };
+        if let (Some(start), Some(end)) = (harmonic_range.start, harmonic_range.end) {
+            if start > end {
+                return Err(invalid());
+            }
+        }
+        Ok(harmonic_range)
     }
-    debug!(
-        "Antinodes are at: {:?},{:?}",
-        (antinode1.x, antinode1.y),
-        (antinode2.x, antinode2.y)
-    );
-    (antinode1, antinode2)
 }
 
-fn calculate_all_antinodes(file_path: Utf8PathBuf, harmonize: bool) -> usize {
-    let (map, width, height) = parse_file(file_path);
-    let mut antinode_map: HashSet<Position> = HashSet::new();
+#[derive(Debug, PartialEq)]
+pub enum Day8Error {
+    InvalidHarmonicRange(String),
+}
 
-    for hash_set in map {
-        if hash_set.len() > 1 {
-            for antenna1 in &hash_set {
-                for antenna2 in &hash_set {
-                    if antenna1 == antenna2 {
-                        continue;
-                    }
-                    if harmonize {
-                        let antinodes = calculate_limitless_antinodes(
-                            antenna1,
-                            antenna2,
-                            width as i32,
-                            height as i32,
-                        );
-                        for node in antinodes {
-                            antinode_map.insert(node);
-                        }
-                    } else {
-                        let (antinode1, antinode2) =
-                            calculate_antinodes(antenna1.to_owned(), antenna2.to_owned());
-                        antinode_map.insert(antinode1);
-                        antinode_map.insert(antinode2);
-                    }
+impl fmt::Display for Day8Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Day8Error::InvalidHarmonicRange(range) => {
+                write!(
+                    f,
+                    "'{}' is not a valid harmonic range, expected e.g. 1..=1, 0.. or 2..=5",
+                    range
+                )
+            }
+        }
+    }
+}
+
+impl error::Error for Day8Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match *self {
+            Day8Error::InvalidHarmonicRange(_) => None,
+        }
+    }
+}
+
+// Antinodes at the chosen multiples of the harmonic unit, beyond antenna2 in the delta's direction and beyond antenna1
+// against it. The unit is the whole delta, or with `lattice` the delta reduced by its gcd so every grid point within a
+// covered stretch of the line counts (e.g. halfway along a (2,4) delta). Both stretches are expressed as gcd reduced
+// steps from antenna2 to merge them.
+fn calculate_harmonic_antinodes(
+    antenna1: &Point,
+    antenna2: &Point,
+    harmonics: &HarmonicRange,
+    map_width: i32,
+    map_height: i32,
+) -> Vec<Point> {
+    let delta_x = antenna2.x - antenna1.x;
+    let delta_y = antenna2.y - antenna1.y;
+    let divisor = gcd(delta_x, delta_y);
+    if divisor == 0 {
+        // Both antennas are in the same spot, so there's no line to follow
+        return vec![];
+    }
+    let step_x = delta_x / divisor;
+    let step_y = delta_y / divisor;
+    let divisor = divisor as i64;
+    debug!("Calculated Step: ({},{})", step_x, step_y);
+
+    let point_at = |steps: i64| Point {
+        x: antenna2.x + (steps * step_x as i64) as i32,
+        y: antenna2.y + (steps * step_y as i64) as i32,
+    };
+    let on_map = |steps: i64| {
+        let point = point_at(steps);
+        is_in_bounds(point.x, point.y, map_width, map_height)
+    };
+
+    // The line crosses the map in one unbroken stretch, which antenna2 is part of
+    let mut first_step = 0;
+    while on_map(first_step - 1) {
+        first_step -= 1;
+    }
+    let mut last_step = 0;
+    while on_map(last_step + 1) {
+        last_step += 1;
+    }
+
+    // antenna2 + k units is k * unit steps away and antenna1 - k units is -(k + antenna_gap) * unit steps away
+    let unit = if harmonics.lattice { 1 } else { divisor };
+    let antenna_gap = divisor / unit;
+    let floor_div = |steps: i64| steps.div_euclid(unit);
+    let ceil_div = |steps: i64| -(-steps).div_euclid(unit);
+    let clamp = |(low, high): (i64, i64)| {
+        (
+            harmonics.start.map_or(low, |start| start.max(low)),
+            harmonics.end.map_or(high, |end| end.min(high)),
+        )
+    };
+    let beyond_antenna2 = clamp((ceil_div(first_step), floor_div(last_step)));
+    let beyond_antenna1 = clamp((
+        ceil_div(-last_step) - antenna_gap,
+        floor_div(-first_step) - antenna_gap,
+    ));
+
+    let mut steps: Vec<i64> = (beyond_antenna2.0..=beyond_antenna2.1)
+        .map(|multiple| multiple * unit)
+        .chain(
+            (beyond_antenna1.0..=beyond_antenna1.1)
+                .map(|multiple| -(multiple + antenna_gap) * unit),
+        )
+        .collect();
+    steps.sort();
+    steps.dedup();
+
+    let antinodes: Vec<Point> = steps.into_iter().map(point_at).collect();
+    debug!("Antinodes: {:?}", antinodes);
+    antinodes
+}
+
+// Antinodes for each frequency. The calculation is symmetric in antenna order, so each pair is only visited once.
+fn generate_antinodes(map: &AntennaMap, harmonics: &HarmonicRange) -> HashMap<char, AntinodeGrid> {
+    let mut antinode_grids: HashMap<char, AntinodeGrid> = HashMap::new();
+
+    for (frequency, antennas) in &map.antennas {
+        let mut antinode_grid = AntinodeGrid::new(map.width, map.height);
+        for (index, antenna1) in antennas.iter().enumerate() {
+            for antenna2 in &antennas[index + 1..] {
+                for node in calculate_harmonic_antinodes(
+                    antenna1,
+                    antenna2,
+                    harmonics,
+                    map.width as i32,
+                    map.height as i32,
+                ) {
+                    antinode_grid.insert(&node);
                 }
             }
         }
+        antinode_grids.insert(*frequency, antinode_grid);
     }
 
-    antinode_map.retain(|position| {
-        position.x >= 0
-            && position.x < width as i32
-            && position.y >= 0
-            && position.y < height as i32
-    });
+    antinode_grids
+}
 
-    let mut deduped_antinode_map: HashSet<Coordinates> = HashSet::new();
-    for node in antinode_map {
-        deduped_antinode_map.insert(Coordinates {
-            x: node.x,
-            y: node.y,
-        });
+fn combine_antinodes(
+    map: &AntennaMap,
+    antinode_grids: &HashMap<char, AntinodeGrid>,
+) -> AntinodeGrid {
+    let mut combined_grid = AntinodeGrid::new(map.width, map.height);
+    for antinode_grid in antinode_grids.values() {
+        combined_grid.union_with(antinode_grid);
     }
+    combined_grid
+}
+
+fn calculate_all_antinodes(file_path: Utf8PathBuf, harmonics: &HarmonicRange) -> usize {
+    let map = parse_file(file_path);
+    let antinode_grids = generate_antinodes(&map, harmonics);
 
-    display_map(&deduped_antinode_map, width, height);
-    debug!("Antinode map {:?}", deduped_antinode_map);
-    deduped_antinode_map.len()
+    info!("Map: [\n{}\n]", render_map(&map, &antinode_grids, false));
+    combine_antinodes(&map, &antinode_grids).len()
 }
 
-fn display_map(map: &HashSet<Coordinates>, width: usize, height: usize) -> () {
-    let mut grid: Vec<Vec<char>> = vec![vec!['.'; width]; height];
-    for position in map {
-        grid[position.y as usize][position.x as usize] = '#';
+// Red, green, yellow, blue, magenta, cyan, then their bright variants
+const FREQUENCY_COLORS: [u8; 12] = [31, 32, 33, 34, 35, 36, 91, 92, 93, 94, 95, 96];
+
+fn paint(cell: char, color_code: u8, color: bool) -> String {
+    if color {
+        format!("\x1b[{}m{}\x1b[0m", color_code, cell)
+    } else {
+        cell.to_string()
+    }
+}
+
+// Antennas are drawn with their frequency, antinodes as '#' (in bold when shared between frequencies).
+// An antenna hides any antinode underneath it, same as the puzzle description.
+fn render_map(
+    map: &AntennaMap,
+    antinode_grids: &HashMap<char, AntinodeGrid>,
+    color: bool,
+) -> String {
+    let frequencies = map.sorted_frequencies();
+    let color_code = |index: usize| -> u8 { FREQUENCY_COLORS[index % FREQUENCY_COLORS.len()] };
+
+    let mut grid: Vec<Vec<String>> = vec![vec![".".to_string(); map.width]; map.height];
+    for (y, row) in grid.iter_mut().enumerate() {
+        for (x, cell) in row.iter_mut().enumerate() {
+            let point = Point {
+                x: x as i32,
+                y: y as i32,
+            };
+            let antinode_frequencies: Vec<usize> = frequencies
+                .iter()
+                .enumerate()
+                .filter(|(_index, frequency)| {
+                    antinode_grids
+                        .get(frequency)
+                        .is_some_and(|antinode_grid| antinode_grid.contains(&point))
+                })
+                .map(|(index, _frequency)| index)
+                .collect();
+            match antinode_frequencies[..] {
+                [] => {}
+                [index] => *cell = paint('#', color_code(index), color),
+                _ => *cell = paint('#', 1, color),
+            }
+        }
     }
-    let mut grid_string = "".to_string();
-    for row in grid {
-        let row_string: String = row.iter().collect();
-        grid_string = [grid_string, row_string, "\n".to_string()].concat();
+    for (index, frequency) in frequencies.iter().enumerate() {
+        for antenna in &map.antennas[frequency] {
+            grid[antenna.y as usize][antenna.x as usize] =
+                paint(*frequency, color_code(index), color);
+        }
     }
-    info!("Map: [\n{}\n]", grid_string);
+
+    grid.iter()
+        .map(|row| row.concat())
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+#[derive(Debug, PartialEq)]
+struct FrequencyBreakdown {
+    frequency: char,
+    antennas: usize,
+    antinodes: usize,
+    // Antinodes of this frequency sitting in the same spot as an antinode of another frequency
+    overlapping_antinodes: usize,
+}
+
+fn frequency_breakdown(
+    map: &AntennaMap,
+    antinode_grids: &HashMap<char, AntinodeGrid>,
+) -> Vec<FrequencyBreakdown> {
+    let mut breakdown = vec![];
+    for frequency in map.sorted_frequencies() {
+        let antinode_grid = &antinode_grids[&frequency];
+        let mut other_antinodes = AntinodeGrid::new(map.width, map.height);
+        for (other_frequency, other_antinode_grid) in antinode_grids {
+            if other_frequency != &frequency {
+                other_antinodes.union_with(other_antinode_grid);
+            }
+        }
+        breakdown.push(FrequencyBreakdown {
+            frequency,
+            antennas: map.antennas[&frequency].len(),
+            antinodes: antinode_grid.len(),
+            overlapping_antinodes: antinode_grid.intersection_len(&other_antinodes),
+        });
+    }
+    breakdown
+}
+
+fn format_breakdown_table(breakdown: &[FrequencyBreakdown]) -> String {
+    let mut table = vec![format!(
+        "{:>9} | {:>8} | {:>9} | {:>11}",
+        "Frequency", "Antennas", "Antinodes", "Overlapping"
+    )];
+    for frequency_breakdown in breakdown {
+        table.push(format!(
+            "{:>9} | {:>8} | {:>9} | {:>11}",
+            frequency_breakdown.frequency,
+            frequency_breakdown.antennas,
+            frequency_breakdown.antinodes,
+            frequency_breakdown.overlapping_antinodes
+        ));
+    }
+    table.join("\n")
+}
+
+fn render_antinodes(
+    file_path: Utf8PathBuf,
+    harmonics: &HarmonicRange,
+    color: bool,
+) -> (String, Vec<FrequencyBreakdown>, usize) {
+    let map = parse_file(file_path);
+    let antinode_grids = generate_antinodes(&map, harmonics);
+    (
+        render_map(&map, &antinode_grids, color),
+        frequency_breakdown(&map, &antinode_grids),
+        combine_antinodes(&map, &antinode_grids).len(),
+    )
 }
 
 #[cfg(test)]
 mod tests {
     use camino::Utf8PathBuf;
+    use proptest::prelude::*;
+
+    use std::collections::HashSet;
 
     use super::*;
     use crate::test_init;
@@ -297,31 +561,23 @@ mod tests {
     #[test]
     fn test_file_input() {
         test_init();
-        let mut hash_set: HashSet<Position> = HashSet::new();
-        hash_set.insert(Position {
-            x: 4,
-            y: 3,
-            frequency: 'a',
-        });
-        hash_set.insert(Position {
-            x: 8,
-            y: 4,
-            frequency: 'a',
-        });
-        hash_set.insert(Position {
-            x: 5,
-            y: 5,
-            frequency: 'a',
-        });
-        let mut hash_set2: HashSet<Position> = HashSet::new();
-        hash_set2.insert(Position {
-            x: 6,
-            y: 7,
-            frequency: 'A',
-        });
-        let map = vec![hash_set, hash_set2];
+        let expected = AntennaMap {
+            antennas: HashMap::from([
+                (
+                    'a',
+                    vec![
+                        Point { x: 4, y: 3 },
+                        Point { x: 8, y: 4 },
+                        Point { x: 5, y: 5 },
+                    ],
+                ),
+                ('A', vec![Point { x: 6, y: 7 }]),
+            ]),
+            width: 10,
+            height: 10,
+        };
         assert_eq!(
-            (map, 10, 10),
+            expected,
             parse_file(Utf8PathBuf::from("./src/puzzle_inputs/day8_sample1.txt"))
         )
     }
@@ -330,30 +586,17 @@ mod tests {
     fn test_antinode_creator() {
         test_init();
 
-        let antenna1 = Position {
-            x: 4,
-            y: 3,
-            frequency: 'a',
-        };
-        let antenna2 = Position {
-            x: 5,
-            y: 5,
-            frequency: 'a',
-        };
+        let antenna1 = Point { x: 4, y: 3 };
+        let antenna2 = Point { x: 5, y: 5 };
         assert_eq!(
-            (
-                Position {
-                    x: 3,
-                    y: 1,
-                    frequency: 'a'
-                },
-                Position {
-                    x: 6,
-                    y: 7,
-                    frequency: 'a'
-                }
-            ),
-            calculate_antinodes(antenna1, antenna2)
+            vec![Point { x: 3, y: 1 }, Point { x: 6, y: 7 }],
+            calculate_harmonic_antinodes(
+                &antenna1,
+                &antenna2,
+                &HarmonicRange::DOUBLE_DISTANCE,
+                10,
+                10
+            )
         )
     }
 
@@ -364,7 +607,7 @@ mod tests {
             14,
             calculate_all_antinodes(
                 Utf8PathBuf::from("./src/puzzle_inputs/day8_sample2.txt"),
-                false
+                &HarmonicRange::DOUBLE_DISTANCE
             )
         )
     }
@@ -376,8 +619,368 @@ mod tests {
             34,
             calculate_all_antinodes(
                 Utf8PathBuf::from("./src/puzzle_inputs/day8_sample2.txt"),
-                true
+                &HarmonicRange::WHOLE_LINE
             )
         )
     }
+
+    #[test]
+    fn test_render_map_from_sample1() {
+        test_init();
+        let (rendered_map, _breakdown, total) = render_antinodes(
+            Utf8PathBuf::from("./src/puzzle_inputs/day8_sample1.txt"),
+            &HarmonicRange::DOUBLE_DISTANCE,
+            false,
+        );
+        assert_eq!(
+            [
+                "..........",
+                "...#......",
+                "#.........",
+                "....a.....",
+                "........a.",
+                ".....a....",
+                "..#.......",
+                "......A...",
+                "..........",
+                "..........",
+            ]
+            .join("\n"),
+            rendered_map
+        );
+        assert_eq!(4, total);
+    }
+
+    #[test]
+    fn test_frequency_breakdown_from_sample1() {
+        test_init();
+        let (_rendered_map, breakdown, _total) = render_antinodes(
+            Utf8PathBuf::from("./src/puzzle_inputs/day8_sample1.txt"),
+            &HarmonicRange::DOUBLE_DISTANCE,
+            false,
+        );
+        assert_eq!(
+            vec![
+                FrequencyBreakdown {
+                    frequency: 'A',
+                    antennas: 1,
+                    antinodes: 0,
+                    overlapping_antinodes: 0
+                },
+                FrequencyBreakdown {
+                    frequency: 'a',
+                    antennas: 3,
+                    antinodes: 4,
+                    overlapping_antinodes: 0
+                }
+            ],
+            breakdown
+        );
+        assert_eq!(
+            [
+                "Frequency | Antennas | Antinodes | Overlapping",
+                "        A |        1 |         0 |           0",
+                "        a |        3 |         4 |           0",
+            ]
+            .join("\n"),
+            format_breakdown_table(&breakdown)
+        );
+    }
+
+    #[test]
+    fn test_render_map_colors_overlapping_antinodes() {
+        test_init();
+        let map = AntennaMap {
+            antennas: HashMap::from([('a', vec![Point { x: 0, y: 0 }]), ('b', vec![])]),
+            width: 3,
+            height: 1,
+        };
+        let mut antinode_grid = AntinodeGrid::new(3, 1);
+        antinode_grid.insert(&Point { x: 1, y: 0 });
+        let antinode_grids = HashMap::from([('a', antinode_grid.clone()), ('b', antinode_grid)]);
+        assert_eq!(
+            "\x1b[31ma\x1b[0m\x1b[1m#\x1b[0m.",
+            render_map(&map, &antinode_grids, true)
+        );
+    }
+
+    #[test]
+    fn test_antinode_grid_ignores_out_of_bounds_points() {
+        test_init();
+        let mut antinode_grid = AntinodeGrid::new(9, 9);
+        antinode_grid.insert(&Point { x: 8, y: 8 });
+        antinode_grid.insert(&Point { x: 8, y: 8 });
+        antinode_grid.insert(&Point { x: -1, y: 0 });
+        antinode_grid.insert(&Point { x: 9, y: 0 });
+        assert_eq!(1, antinode_grid.len());
+        assert!(antinode_grid.contains(&Point { x: 8, y: 8 }));
+        assert!(!antinode_grid.contains(&Point { x: 0, y: 0 }));
+    }
+
+    #[test]
+    fn test_limitless_antinodes_horizontal_right_to_left() {
+        test_init();
+        let antenna1 = Point { x: 5, y: 2 };
+        let antenna2 = Point { x: 3, y: 2 };
+        let antinodes: HashSet<(i32, i32)> =
+            calculate_harmonic_antinodes(&antenna1, &antenna2, &HarmonicRange::WHOLE_LINE, 10, 5)
+                .into_iter()
+                .map(|position| (position.x, position.y))
+                .collect();
+        assert_eq!((0..10).map(|x| (x, 2)).collect::<HashSet<_>>(), antinodes)
+    }
+
+    #[test]
+    fn test_limitless_antinodes_include_gcd_lattice_points() {
+        test_init();
+        let antenna1 = Point { x: 0, y: 0 };
+        let antenna2 = Point { x: 2, y: 4 };
+        assert_eq!(
+            vec![(0, 0), (1, 2), (2, 4)],
+            calculate_harmonic_antinodes(&antenna1, &antenna2, &HarmonicRange::WHOLE_LINE, 5, 5)
+                .into_iter()
+                .map(|position| (position.x, position.y))
+                .collect::<Vec<(i32, i32)>>()
+        )
+    }
+
+    fn antenna_strategy() -> impl Strategy<Value = Point> {
+        (0..20i32, 0..20i32).prop_map(|(x, y)| Point { x, y })
+    }
+
+    fn harmonic_range_strategy() -> impl Strategy<Value = HarmonicRange> {
+        (
+            proptest::option::of(-3..3i64),
+            proptest::option::of(0..4i64),
+            any::<bool>(),
+        )
+            .prop_map(|(start, length, lattice)| HarmonicRange {
+                start,
+                end: length.map(|length| start.unwrap_or(0) + length),
+                lattice,
+            })
+    }
+
+    #[test]
+    fn test_parse_harmonic_ranges() {
+        test_init();
+        assert_eq!(Ok(HarmonicRange::DOUBLE_DISTANCE), "1..=1".parse());
+        assert_eq!(
+            Ok(HarmonicRange::WHOLE_LINE),
+            "..".parse()
+                .map(|range: HarmonicRange| range.with_lattice(true))
+        );
+        for range in ["1..=1", "..", "0..", "..=3", "-2..=5"] {
+            assert_eq!(range, range.parse::<HarmonicRange>().unwrap().to_string());
+        }
+        assert_eq!(
+            Ok(HarmonicRange {
+                start: Some(0),
+                end: None,
+                lattice: false
+            }),
+            "0..".parse()
+        );
+        assert_eq!(
+            Ok(HarmonicRange {
+                start: Some(-1),
+                end: Some(5),
+                lattice: false
+            }),
+            "-1..=5".parse()
+        );
+        for invalid in ["2..5", "1..=", "5..=2", "a..", "3"] {
+            assert_eq!(
+                Err(Day8Error::InvalidHarmonicRange(invalid.to_owned())),
+                invalid.parse::<HarmonicRange>()
+            );
+        }
+    }
+
+    #[test]
+    fn test_harmonic_antinodes_at_chosen_multiples() {
+        test_init();
+        let antenna1 = Point { x: 6, y: 0 };
+        let antenna2 = Point { x: 7, y: 0 };
+        let antinodes = |range: &str| {
+            calculate_harmonic_antinodes(&antenna1, &antenna2, &range.parse().unwrap(), 20, 1)
+                .into_iter()
+                .map(|point| point.x)
+                .collect::<Vec<i32>>()
+        };
+        assert_eq!(vec![5, 8], antinodes("1..=1"));
+        assert_eq!(vec![1, 2, 3, 4, 9, 10, 11, 12], antinodes("2..=5"));
+        assert_eq!(vec![6, 7], antinodes("0..=0"));
+        assert_eq!((0..20).collect::<Vec<i32>>(), antinodes("..=0"));
+        assert_eq!(Vec::<i32>::new(), antinodes("30.."));
+    }
+
+    #[test]
+    fn test_calculate_all_antinodes_from_sample2_from_the_antennas_outward() {
+        test_init();
+        assert_eq!(
+            34,
+            calculate_all_antinodes(
+                Utf8PathBuf::from("./src/puzzle_inputs/day8_sample2.txt"),
+                &"0..".parse().unwrap()
+            )
+        )
+    }
+
+    #[test]
+    fn test_bounded_harmonics_skip_partial_multiples() {
+        test_init();
+        let mut antinodes = calculate_harmonic_antinodes(
+            &Point { x: 2, y: 2 },
+            &Point { x: 4, y: 4 },
+            &"1..=2".parse().unwrap(),
+            9,
+            9,
+        );
+        antinodes.sort_by_key(|point| (point.x, point.y));
+        assert_eq!(
+            vec![
+                Point { x: 0, y: 0 },
+                Point { x: 6, y: 6 },
+                Point { x: 8, y: 8 }
+            ],
+            antinodes
+        );
+    }
+
+    #[test]
+    fn test_harmonic_ranges_agree_on_non_coprime_deltas() {
+        test_init();
+        let antinodes = |range: &str, lattice: bool| {
+            let harmonics = range
+                .parse::<HarmonicRange>()
+                .unwrap()
+                .with_lattice(lattice);
+            let mut antinodes = calculate_harmonic_antinodes(
+                &Point { x: 2, y: 0 },
+                &Point { x: 4, y: 4 },
+                &harmonics,
+                9,
+                9,
+            )
+            .into_iter()
+            .map(|point| (point.x, point.y))
+            .collect::<Vec<(i32, i32)>>();
+            antinodes.sort();
+            antinodes
+        };
+        assert_eq!(vec![(2, 0), (4, 4), (6, 8)], antinodes("..", false));
+        assert_eq!(antinodes("..", false), antinodes("-1000..=1000", false));
+        assert_eq!(
+            vec![(2, 0), (3, 2), (4, 4), (5, 6), (6, 8)],
+            antinodes("..", true)
+        );
+        assert_eq!(antinodes("..", true), antinodes("-1000..=1000", true));
+        assert_eq!(vec![(5, 6)], antinodes("1..=1", true));
+    }
+
+    proptest! {
+        #[test]
+        fn prop_antinodes_symmetric_in_antenna_order(
+            antenna1 in antenna_strategy(),
+            antenna2 in antenna_strategy(),
+            harmonics in harmonic_range_strategy()
+        ) {
+            prop_assume!(antenna1 != antenna2);
+            let forward: HashSet<Point> =
+                calculate_harmonic_antinodes(&antenna1, &antenna2, &harmonics, 20, 20).into_iter().collect();
+            let backward: HashSet<Point> =
+                calculate_harmonic_antinodes(&antenna2, &antenna1, &harmonics, 20, 20).into_iter().collect();
+            prop_assert_eq!(forward, backward);
+        }
+
+        #[test]
+        fn prop_limitless_antinodes_symmetric_in_antenna_order(
+            antenna1 in antenna_strategy(),
+            antenna2 in antenna_strategy()
+        ) {
+            prop_assume!(antenna1 != antenna2);
+            let forward: HashSet<Point> =
+                calculate_harmonic_antinodes(&antenna1, &antenna2, &HarmonicRange::WHOLE_LINE, 20, 20).into_iter().collect();
+            let backward: HashSet<Point> =
+                calculate_harmonic_antinodes(&antenna2, &antenna1, &HarmonicRange::WHOLE_LINE, 20, 20).into_iter().collect();
+            prop_assert!(forward.contains(&antenna1) && forward.contains(&antenna2));
+            prop_assert_eq!(forward, backward);
+        }
+
+        #[test]
+        fn prop_limitless_antinodes_are_collinear_lattice_points(
+            antenna1 in antenna_strategy(),
+            antenna2 in antenna_strategy()
+        ) {
+            prop_assume!(antenna1 != antenna2);
+            let antinodes: HashSet<(i32, i32)> =
+                calculate_harmonic_antinodes(&antenna1, &antenna2, &HarmonicRange::WHOLE_LINE, 20, 20)
+                    .into_iter()
+                    .map(|position| (position.x, position.y))
+                    .collect();
+            // Brute force every grid point, keeping those with a zero cross product against the antenna delta
+            let delta_x = antenna2.x - antenna1.x;
+            let delta_y = antenna2.y - antenna1.y;
+            let mut expected = HashSet::new();
+            for y in 0..20 {
+                for x in 0..20 {
+                    if (x - antenna1.x) * delta_y == (y - antenna1.y) * delta_x {
+                        expected.insert((x, y));
+                    }
+                }
+            }
+            prop_assert_eq!(expected, antinodes);
+        }
+
+        #[test]
+        fn prop_harmonic_antinodes_match_brute_force(
+            antenna1 in antenna_strategy(),
+            antenna2 in antenna_strategy(),
+            harmonics in harmonic_range_strategy()
+        ) {
+            prop_assume!(antenna1 != antenna2);
+            let antinodes: HashSet<Point> =
+                calculate_harmonic_antinodes(&antenna1, &antenna2, &harmonics, 20, 20).into_iter().collect();
+            // A point in line with both antennas is antenna2 + k * unit, or antenna1 - k * unit, with k recovered
+            // through the dot product (scaled by the unit's squared length to stay in integers). The unit is the delta,
+            // or the smallest lattice step along it
+            let delta_x = (antenna2.x - antenna1.x) as i64;
+            let delta_y = (antenna2.y - antenna1.y) as i64;
+            let reduction = if harmonics.lattice {
+                (1..=delta_x.abs().max(delta_y.abs()))
+                    .filter(|divisor| delta_x % divisor == 0 && delta_y % divisor == 0)
+                    .max()
+                    .unwrap()
+            } else {
+                1
+            };
+            let (unit_x, unit_y) = (delta_x / reduction, delta_y / reduction);
+            let length_squared = unit_x * unit_x + unit_y * unit_y;
+            let in_range = |scaled_multiple: i64| {
+                if scaled_multiple % length_squared != 0 {
+                    return false;
+                }
+                let multiple = scaled_multiple / length_squared;
+                harmonics.start.is_none_or(|start| start <= multiple)
+                    && harmonics.end.is_none_or(|end| multiple <= end)
+            };
+            let mut expected = HashSet::new();
+            for y in 0..20 {
+                for x in 0..20 {
+                    let from_antenna2 = ((x - antenna2.x) as i64, (y - antenna2.y) as i64);
+                    let from_antenna1 = ((antenna1.x - x) as i64, (antenna1.y - y) as i64);
+                    if from_antenna2.0 * delta_y != from_antenna2.1 * delta_x {
+                        continue;
+                    }
+                    if in_range(from_antenna2.0 * unit_x + from_antenna2.1 * unit_y)
+                        || in_range(from_antenna1.0 * unit_x + from_antenna1.1 * unit_y)
+                    {
+                        expected.insert(Point { x, y });
+                    }
+                }
+            }
+            prop_assert_eq!(expected, antinodes);
+        }
+    }
 }
@@ -178,8 +178,10 @@ fn sort_disk_map(disk: Vec<Block>) -> Vec<Block> {
 
 fn calculate_file_checksum(filepath: Utf8PathBuf) -> u64 {
     let content = read_file(filepath);
-    let sorted_disk = sort_disk_map(parse_disk_map(&content));
+    disk_checksum(&sort_disk_map(parse_disk_map(&content)))
+}
 
+fn disk_checksum(sorted_disk: &[Block]) -> u64 {
     let mut checksum = 0;
     for (index, block) in sorted_disk.iter().enumerate() {
         match block {
@@ -279,6 +281,7 @@ fn sort_disk_map_block2(disk: Vec<Block2>) -> Vec<Block2> {
 #[cfg(test)]
 mod tests {
     use camino::Utf8PathBuf;
+    use proptest::prelude::*;
 
     use super::*;
     use crate::test_init;
@@ -430,4 +433,58 @@ mod tests {
             sort_disk_map_block2(parse_disk_map_block2("2333133121414131402"))
         ) //00992111777.44.333....5555.6666.....8888..
     }
+
+    // Moves the last file block into the first empty block, one block at a time, until no gaps are left between files
+    fn compact_by_swaps(mut disk: Vec<Block>) -> Vec<Block> {
+        loop {
+            let first_empty = disk.iter().position(|block| block == &Block::Empty);
+            let last_file = disk.iter().rposition(|block| block != &Block::Empty);
+            match (first_empty, last_file) {
+                (Some(empty_index), Some(file_index)) if empty_index < file_index => {
+                    disk.swap(empty_index, file_index)
+                }
+                _ => return disk,
+            }
+        }
+    }
+
+    fn disk_map_strategy() -> impl Strategy<Value = String> {
+        proptest::collection::vec(0u32..10, 0..20).prop_map(|digits| {
+            digits
+                .into_iter()
+                .map(|digit| char::from_digit(digit, 10).expect("digit to be below 10"))
+                .collect()
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn prop_parse_disk_map_expands_digits(disk in disk_map_strategy()) {
+            let parsed_disk = parse_disk_map(&disk);
+            let mut expected = vec![];
+            for (index, digit) in disk.chars().enumerate() {
+                let length = digit.to_digit(10).expect("digit") as usize;
+                let block = if index % 2 == 0 { Block::File(index / 2) } else { Block::Empty };
+                expected.extend(std::iter::repeat_n(block, length));
+            }
+            prop_assert_eq!(expected, parsed_disk);
+        }
+
+        #[test]
+        fn prop_sort_disk_map_matches_block_swaps(disk in disk_map_strategy()) {
+            let parsed_disk = parse_disk_map(&disk);
+            let expected = compact_by_swaps(parsed_disk.clone());
+            let sorted_disk = sort_disk_map(parsed_disk);
+            let expected_checksum: u64 = expected
+                .iter()
+                .enumerate()
+                .map(|(index, block)| match block {
+                    Block::File(file_index) => (index * file_index) as u64,
+                    Block::Empty => 0,
+                })
+                .sum();
+            prop_assert_eq!(expected_checksum, disk_checksum(&sorted_disk));
+            prop_assert_eq!(expected, sorted_disk);
+        }
+    }
 }